@@ -8,6 +8,7 @@ use test::{Bencher, black_box};
 use attr::retrieve;
 use attr::Traverse;
 
+#[allow(dead_code)]
 trait Attributes<AttributeType> {
     fn attrs() -> AttributeType;
 }
@@ -144,7 +145,7 @@ pub mod foo {
     impl<'a> IterableAttr<'a, &'a Foo> for Numbers {
         type Item = &'a i32;
 
-        fn iter(&self, i: &'a Foo) -> Box<Iterator<Item=&'a i32> + 'a> {
+        fn iter(&self, i: &'a Foo) -> Box<dyn Iterator<Item=&'a i32> + 'a> {
             Box::new(self.get(i).iter())
         }
     }
@@ -152,7 +153,7 @@ pub mod foo {
     impl<'a> IterableAttr<'a, &'a mut Foo> for Numbers {
         type Item = &'a mut i32;
 
-        fn iter(&self, i: &'a mut Foo) -> Box<Iterator<Item=&'a mut i32> +'a> {
+        fn iter(&self, i: &'a mut Foo) -> Box<dyn Iterator<Item=&'a mut i32> +'a> {
             Box::new(self.get(i).iter_mut())
         }
     }
@@ -221,7 +222,7 @@ fn through_path(b: &mut Bencher) {
 }
 
 #[inline]
-fn path_access(f: &Foo) -> Result<&str, String> {
+fn path_access(f: &Foo) -> attr::Result<&str> {
     let p = retrieve(bla::Name).from(foo::Batz);
     p.traverse(f)
 }
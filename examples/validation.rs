@@ -45,7 +45,7 @@ impl<P> PrefixValidator<P> {
                     Err(format!("Does not start with {}", self.pattern))
                 }
             }
-            Err(reason) => Err(reason)
+            Err(reason) => Err(reason.to_string())
         }
     }
 }
@@ -57,7 +57,7 @@ fn main() {
 
 fn validate(u: &User) -> std::result::Result<(), String> {
     let path = retrieve(EmailAttribute).from(DataAttribute);
-    let validator = PrefixValidator { pattern: "flo".into(), path: path };
+    let validator = PrefixValidator { pattern: "flo".into(), path };
 
     validator.validate(u)
 }
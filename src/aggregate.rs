@@ -0,0 +1,86 @@
+use std::iter::Sum;
+
+use super::{Traverse, Result};
+
+fn indexed<'a, Z: 'a>(iter: Box<dyn Iterator<Item=Result<Z>> + 'a>) -> impl Iterator<Item=Result<Z>> + 'a {
+    iter.enumerate().map(|(i, item)| item.map_err(|e| e.with_index(i)))
+}
+
+/// Terminal operations on paths that iterate, such as `MapPath`.
+///
+/// All operations stop at the first item that failed to traverse
+/// and return its error, with the index of the item recorded.
+///
+/// This trait is implemented for all applicable paths.
+pub trait Aggregate<'a, X: 'a, Z: 'a> : Traverse<'a, 'a, X, Box<dyn Iterator<Item=Result<Z>> + 'a>> {
+    /// Counts all items.
+    fn count(&'a self, obj: X) -> Result<usize> {
+        self.fold(obj, 0, |n, _| n + 1)
+    }
+
+    /// Sums up all items.
+    fn sum<S: Sum<Z>>(&'a self, obj: X) -> Result<S> {
+        indexed(self.traverse(obj)?).sum()
+    }
+
+    /// The smallest item, if there are any.
+    fn min(&'a self, obj: X) -> Result<Option<Z>> where Z: Ord {
+        self.fold(obj, None, |min: Option<Z>, z| match min {
+            Some(m) if m <= z => Some(m),
+            _ => Some(z),
+        })
+    }
+
+    /// The largest item, if there are any.
+    fn max(&'a self, obj: X) -> Result<Option<Z>> where Z: Ord {
+        self.fold(obj, None, |max: Option<Z>, z| match max {
+            Some(m) if m > z => Some(m),
+            _ => Some(z),
+        })
+    }
+
+    /// Whether any item matches the predicate.
+    ///
+    /// Stops at the first match.
+    fn any<F: FnMut(Z) -> bool>(&'a self, obj: X, mut predicate: F) -> Result<bool> {
+        for item in indexed(self.traverse(obj)?) {
+            if predicate(item?) {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    /// Whether all items match the predicate.
+    ///
+    /// Stops at the first item that doesn't match.
+    fn all<F: FnMut(Z) -> bool>(&'a self, obj: X, mut predicate: F) -> Result<bool> {
+        for item in indexed(self.traverse(obj)?) {
+            if !predicate(item?) {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    /// The first item, if there is any.
+    fn first(&'a self, obj: X) -> Result<Option<Z>> {
+        indexed(self.traverse(obj)?).next().map_or(Ok(None), |item| item.map(Some))
+    }
+
+    /// Collects all items into a vector.
+    fn collect_vec(&'a self, obj: X) -> Result<Vec<Z>> {
+        indexed(self.traverse(obj)?).collect()
+    }
+
+    /// Folds all items into a single value.
+    fn fold<B, F: FnMut(B, Z) -> B>(&'a self, obj: X, init: B, mut f: F) -> Result<B> {
+        let mut acc = init;
+        for item in indexed(self.traverse(obj)?) {
+            acc = f(acc, item?);
+        }
+        Ok(acc)
+    }
+}
+
+impl<'a, X: 'a, Z: 'a, P: Traverse<'a, 'a, X, Box<dyn Iterator<Item=Result<Z>> + 'a>>> Aggregate<'a, X, Z> for P {}
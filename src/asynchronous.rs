@@ -0,0 +1,400 @@
+//! Asynchronous attributes and paths, available with the `async` feature.
+//!
+//! `AsyncAttr` and `AsyncInsecureAttr` return boxed futures, for example
+//! to load a row from a database. Asynchronous segments mix freely with
+//! synchronous ones, and iterations return a `Stream` through `mapped_async`.
+//!
+//! ```edition2018
+//! # extern crate futures;
+//! # use std::collections::HashMap;
+//! # use attr::{retrieve, Attr, AsyncInsecureAttr, AsyncTraverse, Error, Result};
+//! # use attr::asynchronous::BoxFuture;
+//! # struct Shipment { order: Order }
+//! # struct Order { customer: u32 }
+//! # struct Customer { email: String }
+//! # struct OrderAttribute;
+//! # struct EmailAttribute;
+//! # struct LoadCustomer<'d>(&'d HashMap<u32, Customer>);
+//! # impl<'a> Attr<&'a Shipment> for OrderAttribute { type Output = &'a Order; fn get(&self, s: &'a Shipment) -> &'a Order { &s.order } fn name(&self) -> &str { "order" } }
+//! # impl<'a> Attr<&'a Customer> for EmailAttribute { type Output = &'a str; fn get(&self, c: &'a Customer) -> &'a str { &c.email } fn name(&self) -> &str { "email" } }
+//! # impl<'a, 'o, 'd> AsyncInsecureAttr<'a, &'o Order> for LoadCustomer<'d> { type Output = &'d Customer; fn name(&self) -> &str { "customer" } fn get(&'a self, o: &'o Order) -> BoxFuture<'a, Result<&'d Customer>> { Box::pin(std::future::ready(self.0.get(&o.customer).ok_or_else(|| Error::missing("no customer")))) } }
+//! # let mut db = HashMap::new();
+//! # db.insert(1, Customer { email: "flo@example.com".into() });
+//! # let shipment = Shipment { order: Order { customer: 1 } };
+//! # futures::executor::block_on(async {
+//! let path = retrieve(EmailAttribute).try_async(LoadCustomer(&db)).from(OrderAttribute);
+//! let email = path.traverse_async(&shipment).await?;
+//! # assert_eq!(email, "flo@example.com");
+//! # Ok::<(), Error>(())
+//! # }).unwrap();
+//! ```
+
+use std::fmt;
+use std::future::{self, Future};
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures_core::Stream;
+
+use super::{Attr, InsecureAttr, IterableAttr, Identity, Path, InsecurePath, PathInfo, Segment, SegmentKind, Result};
+
+/// A boxed future, as returned by async attributes and traversals.
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output=T> + 'a>>;
+
+/// A boxed stream, as returned by traversing a `StreamPath`.
+pub type BoxStream<'a, T> = Pin<Box<dyn Stream<Item=T> + 'a>>;
+
+/// Asynchronous access to an attribute of a type, for example
+/// a lazily loaded row.
+///
+/// All attributes need to be named for debugging purposes.
+pub trait AsyncAttr<'a, Type> {
+    /// The resulting value when accessing the attribute
+    type Output;
+
+    /// The attributes name
+    fn name(&self) -> &str;
+    /// Implementation of the retrieval
+    fn get(&'a self, i: Type) -> BoxFuture<'a, Self::Output>;
+}
+
+/// Asynchronous, possibly failing access to an attribute of a type.
+pub trait AsyncInsecureAttr<'a, Type> {
+    /// The resulting value when accessing the attribute
+    type Output;
+
+    /// The attributes name
+    fn name(&self) -> &str;
+    /// Implementation of the retrieval
+    fn get(&'a self, i: Type) -> BoxFuture<'a, Result<Self::Output>>;
+}
+
+/// Asynchronous path traversal.
+///
+/// Implemented by all paths that can be traversed asynchronously,
+/// which includes plain paths of synchronous segments.
+pub trait AsyncTraverse<'a, 'b: 'a, X: 'b, Y: 'b> {
+    /// implementation of the traversal for a specific path
+    ///
+    /// Like most futures, the returned future panics when polled again
+    /// after it completed.
+    fn traverse_async(&'a self, val: X) -> BoxFuture<'a, Result<Y>>;
+}
+
+/// A synchronous attribute used as a segment of an asynchronous path.
+///
+/// Created when extending async paths through `from` and `try`.
+pub struct Immediate<A>(A);
+
+impl<'a, X, A: Attr<X>> AsyncAttr<'a, X> for Immediate<A> where A::Output: 'a {
+    type Output = A::Output;
+
+    fn name(&self) -> &str {
+        self.0.name()
+    }
+
+    fn get(&'a self, i: X) -> BoxFuture<'a, A::Output> {
+        Box::pin(future::ready(self.0.get(i)))
+    }
+}
+
+impl<'a, X, A: InsecureAttr<X>> AsyncInsecureAttr<'a, X> for Immediate<A> where A::Output: 'a {
+    type Output = A::Output;
+
+    fn name(&self) -> &str {
+        self.0.name()
+    }
+
+    fn get(&'a self, i: X) -> BoxFuture<'a, Result<A::Output>> {
+        Box::pin(future::ready(self.0.get(i)))
+    }
+}
+
+/// A path retrieving a value through an `AsyncAttr`, and then
+/// recursing down the rest of the path.
+///
+/// Paths are usually inferred and should not be directly used
+/// in user code.
+pub struct AsyncPath<Input, Output, A, Rest> {
+    attr: A,
+    next: Rest,
+    phantom_x: PhantomData<Input>,
+    phantom_z: PhantomData<Output>,
+}
+
+/// A path retrieving a value through an `AsyncInsecureAttr`, and then
+/// recursing down the rest of the path.
+///
+/// Paths are usually inferred and should not be directly used
+/// in user code.
+pub struct AsyncInsecurePath<Input, Output, A, Rest> {
+    attr: A,
+    next: Rest,
+    phantom_x: PhantomData<Input>,
+    phantom_z: PhantomData<Output>,
+}
+
+/// The asynchronous equivalent of `MapPath`: applies an asynchronous
+/// subpath to every item and returns the results as a `Stream`.
+///
+/// Items are processed one after the other, in order. The stream returns
+/// `None` again when polled after it ended.
+///
+/// Paths are usually inferred and should not be directly used
+/// in user code.
+pub struct StreamPath<Input, A, R> {
+    attr: A,
+    next: R,
+    phantom_x: PhantomData<Input>,
+}
+
+/// `retrieve_async` is the starting point of a path whose
+/// innermost segment is asynchronous.
+pub fn retrieve_async<'a, X, Z, A>(attr: A) -> AsyncPath<X, Z, A, Identity>
+    where A: AsyncAttr<'a, X>
+{
+    AsyncPath { attr, next: Identity, phantom_x: PhantomData, phantom_z: PhantomData }
+}
+
+/// `retrieve_async_insecure` is the starting point of a path whose
+/// innermost segment is asynchronous and may fail.
+pub fn retrieve_async_insecure<'a, X, Z, A>(attr: A) -> AsyncInsecurePath<X, Z, A, Identity>
+    where A: AsyncInsecureAttr<'a, X>
+{
+    AsyncInsecurePath { attr, next: Identity, phantom_x: PhantomData, phantom_z: PhantomData }
+}
+
+/// Runs `first`, then the future returned by `f`.
+///
+/// Panics when polled after completion, which the `Future` contract allows.
+struct Then<'a, T, U, F> {
+    first: Option<BoxFuture<'a, T>>,
+    f: Option<F>,
+    second: Option<BoxFuture<'a, U>>,
+}
+
+fn then<'a, T: 'a, U: 'a, F>(first: BoxFuture<'a, T>, f: F) -> BoxFuture<'a, U>
+    where F: FnOnce(T) -> BoxFuture<'a, U> + Unpin + 'a
+{
+    Box::pin(Then { first: Some(first), f: Some(f), second: None })
+}
+
+impl<'a, T, U, F: FnOnce(T) -> BoxFuture<'a, U> + Unpin> Future for Then<'a, T, U, F> {
+    type Output = U;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<U> {
+        let this = self.get_mut();
+
+        if let Some(ref mut first) = this.first {
+            match first.as_mut().poll(cx) {
+                Poll::Ready(value) => {
+                    let f = this.f.take().expect("polled after completion");
+                    this.second = Some(f(value));
+                },
+                Poll::Pending => return Poll::Pending,
+            }
+            this.first = None;
+        }
+
+        match this.second {
+            Some(ref mut second) => second.as_mut().poll(cx),
+            None => panic!("polled after completion"),
+        }
+    }
+}
+
+/// The stream returned by traversing a `StreamPath`.
+///
+/// The items are fused, so polling after the end keeps returning `None`
+/// instead of panicking.
+struct MapStream<'a, I, Z, F> {
+    items: Box<dyn Iterator<Item=I> + 'a>,
+    f: F,
+    current: Option<BoxFuture<'a, Result<Z>>>,
+}
+
+impl<'a, I, Z, F: FnMut(I) -> BoxFuture<'a, Result<Z>> + Unpin> Stream for MapStream<'a, I, Z, F> {
+    type Item = Result<Z>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Result<Z>>> {
+        let this = self.get_mut();
+
+        if this.current.is_none() {
+            match this.items.next() {
+                Some(item) => this.current = Some((this.f)(item)),
+                None => return Poll::Ready(None),
+            }
+        }
+
+        let result = match this.current {
+            Some(ref mut current) => match current.as_mut().poll(cx) {
+                Poll::Ready(result) => result,
+                Poll::Pending => return Poll::Pending,
+            },
+            None => return Poll::Ready(None),
+        };
+
+        this.current = None;
+        Poll::Ready(Some(result))
+    }
+}
+
+impl<'a, 'b: 'a, T: 'b> AsyncTraverse<'a, 'b, T, T> for Identity {
+    #[inline]
+    fn traverse_async(&'a self, val: T) -> BoxFuture<'a, Result<T>> {
+        Box::pin(future::ready(Ok(val)))
+    }
+}
+
+impl<'a, 'b: 'a, X: 'b, Z: 'b, A: Attr<X>, R: AsyncTraverse<'a, 'b, A::Output, Z>> AsyncTraverse<'a, 'b, X, Z> for Path<X, Z, A, R> where <A as Attr<X>>::Output: 'b {
+    #[inline]
+    fn traverse_async(&'a self, obj: X) -> BoxFuture<'a, Result<Z>> {
+        self.next.traverse_async(self.attr.get(obj))
+    }
+}
+
+impl<'a, 'b: 'a, X: 'b, Z: 'b, A: InsecureAttr<X>, R: AsyncTraverse<'a, 'b, A::Output, Z>> AsyncTraverse<'a, 'b, X, Z> for InsecurePath<X, Z, A, R> where <A as InsecureAttr<X>>::Output: 'b {
+    #[inline]
+    fn traverse_async(&'a self, obj: X) -> BoxFuture<'a, Result<Z>> {
+        match self.attr.get(obj) {
+            Ok(val) => self.next.traverse_async(val),
+            Err(e) => Box::pin(future::ready(Err(e))),
+        }
+    }
+}
+
+impl<'a, 'b: 'a, X: 'b, Z: 'b, A: AsyncAttr<'a, X>, R: AsyncTraverse<'a, 'b, A::Output, Z>> AsyncTraverse<'a, 'b, X, Z> for AsyncPath<X, Z, A, R> where A::Output: 'b {
+    fn traverse_async(&'a self, obj: X) -> BoxFuture<'a, Result<Z>> {
+        let next = &self.next;
+        then(self.attr.get(obj), move |val| next.traverse_async(val))
+    }
+}
+
+impl<'a, 'b: 'a, X: 'b, Z: 'b, A: AsyncInsecureAttr<'a, X>, R: AsyncTraverse<'a, 'b, A::Output, Z>> AsyncTraverse<'a, 'b, X, Z> for AsyncInsecurePath<X, Z, A, R> where A::Output: 'b {
+    fn traverse_async(&'a self, obj: X) -> BoxFuture<'a, Result<Z>> {
+        let next = &self.next;
+        then(self.attr.get(obj), move |val| match val {
+            Ok(val) => next.traverse_async(val),
+            Err(e) => Box::pin(future::ready(Err(e))),
+        })
+    }
+}
+
+impl<'a, X: 'a, Z: 'a, A: IterableAttr<'a, X>, R: AsyncTraverse<'a, 'a, A::Item, Z>> AsyncTraverse<'a, 'a, X, BoxStream<'a, Result<Z>>> for StreamPath<X, A, R> {
+    fn traverse_async(&'a self, obj: X) -> BoxFuture<'a, Result<BoxStream<'a, Result<Z>>>> {
+        let next = &self.next;
+        let stream: BoxStream<'a, Result<Z>> = Box::pin(MapStream {
+            items: Box::new(self.attr.iter(obj).fuse()),
+            f: move |item| next.traverse_async(item),
+            current: None,
+        });
+        Box::pin(future::ready(Ok(stream)))
+    }
+}
+
+macro_rules! async_combinators {
+    () => {
+        /// Extends a path by an asynchronous segment.
+        pub fn from_async<'a, 'b: 'a, NY: 'b, NZ: 'b, NX: 'b, NA>(self, attr: NA) -> AsyncPath<NX, NZ, NA, Self>
+            where NA: AsyncAttr<'a, NX, Output=NY>,
+                  Self: AsyncTraverse<'a, 'b, NY, NZ> {
+            AsyncPath { attr, next: self, phantom_x: PhantomData, phantom_z: PhantomData }
+        }
+
+        /// Extends a path by an asynchronous segment that may fail.
+        pub fn try_async<'a, 'b: 'a, NY: 'b, NZ: 'b, NX: 'b, NA>(self, attr: NA) -> AsyncInsecurePath<NX, NZ, NA, Self>
+            where NA: AsyncInsecureAttr<'a, NX, Output=NY>,
+                  Self: AsyncTraverse<'a, 'b, NY, NZ> {
+            AsyncInsecurePath { attr, next: self, phantom_x: PhantomData, phantom_z: PhantomData }
+        }
+
+        /// Extends a path by an iteration, returning the results as a `Stream`
+        /// when traversed asynchronously.
+        pub fn mapped_async<'a, NX: 'a, NY: 'a, NZ: 'a, NA>(self, attr: NA) -> StreamPath<NX, NA, Self>
+            where NA: IterableAttr<'a, NX, Item=NY>,
+                  Self: AsyncTraverse<'a, 'a, NY, NZ> {
+            StreamPath { attr, next: self, phantom_x: PhantomData }
+        }
+    }
+}
+
+macro_rules! immediate_combinators {
+    () => {
+        /// Extends an asynchronous path by a synchronous segment that always succeeds.
+        pub fn from<'a, 'b: 'a, NY: 'b, NZ: 'b, NX: 'b, NA>(self, attr: NA) -> AsyncPath<NX, NZ, Immediate<NA>, Self>
+            where NA: Attr<NX, Output=NY>,
+                  Self: AsyncTraverse<'a, 'b, NY, NZ> {
+            AsyncPath { attr: Immediate(attr), next: self, phantom_x: PhantomData, phantom_z: PhantomData }
+        }
+
+        /// Extends an asynchronous path by a synchronous segment that may fail.
+        pub fn try<'a, 'b: 'a, NY: 'b, NZ: 'b, NX: 'b, NA>(self, attr: NA) -> AsyncInsecurePath<NX, NZ, Immediate<NA>, Self>
+            where NA: InsecureAttr<NX, Output=NY>,
+                  Self: AsyncTraverse<'a, 'b, NY, NZ> {
+            AsyncInsecurePath { attr: Immediate(attr), next: self, phantom_x: PhantomData, phantom_z: PhantomData }
+        }
+    }
+}
+
+impl<X, Z, A: Attr<X>, R> Path<X, Z, A, R> {
+    async_combinators!();
+}
+
+impl<X, Z, A: InsecureAttr<X>, R> InsecurePath<X, Z, A, R> {
+    async_combinators!();
+}
+
+impl<X, Z, A, R> AsyncPath<X, Z, A, R> {
+    async_combinators!();
+    immediate_combinators!();
+}
+
+impl<X, Z, A, R> AsyncInsecurePath<X, Z, A, R> {
+    async_combinators!();
+    immediate_combinators!();
+}
+
+impl<X, A, R> StreamPath<X, A, R> {
+    async_combinators!();
+    immediate_combinators!();
+}
+
+impl<'a, X, Z, A: AsyncAttr<'a, X>, R: PathInfo> PathInfo for AsyncPath<X, Z, A, R> {
+    fn push_segments<'s>(&'s self, segments: &mut Vec<Segment<'s>>) {
+        segments.push(Segment { name: self.attr.name(), kind: SegmentKind::Attr });
+        self.next.push_segments(segments);
+    }
+}
+
+impl<'a, X, Z, A: AsyncInsecureAttr<'a, X>, R: PathInfo> PathInfo for AsyncInsecurePath<X, Z, A, R> {
+    fn push_segments<'s>(&'s self, segments: &mut Vec<Segment<'s>>) {
+        segments.push(Segment { name: self.attr.name(), kind: SegmentKind::Insecure });
+        self.next.push_segments(segments);
+    }
+}
+
+impl<'a, X, A: IterableAttr<'a, X>, R: PathInfo> PathInfo for StreamPath<X, A, R> {
+    fn push_segments<'s>(&'s self, segments: &mut Vec<Segment<'s>>) {
+        segments.push(Segment { name: self.attr.name(), kind: SegmentKind::Iterable });
+        self.next.push_segments(segments);
+    }
+}
+
+impl<'a, X, Z, A: AsyncAttr<'a, X>, R: PathInfo> fmt::Display for AsyncPath<X, Z, A, R> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.descriptor().fmt(f)
+    }
+}
+
+impl<'a, X, Z, A: AsyncInsecureAttr<'a, X>, R: PathInfo> fmt::Display for AsyncInsecurePath<X, Z, A, R> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.descriptor().fmt(f)
+    }
+}
+
+impl<'a, X, A: IterableAttr<'a, X>, R: PathInfo> fmt::Display for StreamPath<X, A, R> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.descriptor().fmt(f)
+    }
+}
@@ -0,0 +1,113 @@
+//! Evaluates a query on JSON documents and prints the selected values,
+//! one per line.
+//!
+//! ```text
+//! attr QUERY [FILE...]
+//! ```
+//!
+//! Documents are read from the given files, or from stdin if there are
+//! none. Each input may contain several whitespace separated documents.
+//! The query may be a dotted path, a JSON Pointer or a JSONPath subset,
+//! see `attr::json::Query`.
+//!
+//! Exits with 1 if the query failed on any document and with 2 on usage
+//! or input errors.
+
+extern crate attr;
+extern crate serde_json;
+
+use std::env;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::process;
+
+use serde_json::Value;
+
+use attr::json::Query;
+
+const USAGE: &str = "usage: attr QUERY [FILE...]
+
+Prints the values selected by QUERY in each JSON document, one per line.
+Documents are read from FILEs, or from stdin if none are given.
+
+QUERY is one of
+  user.orders[*].id      a dotted path
+  /user/orders/0/id      a JSON Pointer
+  $.user.orders[*].id    a JSONPath subset ($, .name, ['name'], [0], .*, [*])";
+
+fn main() {
+    let args = env::args().skip(1).collect::<Vec<_>>();
+
+    if args.iter().any(|a| a == "-h" || a == "--help") {
+        println!("{}", USAGE);
+        return;
+    }
+
+    let (query, files) = match args.split_first() {
+        Some((query, files)) => (query, files),
+        None => {
+            eprintln!("{}", USAGE);
+            process::exit(2);
+        }
+    };
+
+    let query = match Query::parse(query) {
+        Ok(query) => query,
+        Err(e) => {
+            eprintln!("attr: invalid query: {}", e.message());
+            process::exit(2);
+        }
+    };
+
+    let stdout = io::stdout();
+    let mut out = BufWriter::new(stdout.lock());
+
+    let code = if files.is_empty() {
+        run(&query, "<stdin>", io::stdin().lock(), &mut out)
+    } else {
+        files.iter().map(|name| match File::open(name) {
+            Ok(file) => run(&query, name, BufReader::new(file), &mut out),
+            Err(e) => {
+                eprintln!("attr: {}: {}", name, e);
+                2
+            },
+        }).max().unwrap_or(0)
+    };
+
+    if let Err(e) = out.flush() {
+        eprintln!("attr: {}", e);
+        process::exit(2);
+    }
+
+    process::exit(code);
+}
+
+/// Evaluates `query` on all documents in `input`, returning the exit code.
+fn run<R: Read, W: Write>(query: &Query, name: &str, input: R, out: &mut W) -> i32 {
+    let mut code = 0;
+
+    for (i, document) in serde_json::Deserializer::from_reader(input).into_iter::<Value>().enumerate() {
+        let document = match document {
+            Ok(document) => document,
+            Err(e) => {
+                eprintln!("attr: {}: {}", name, e);
+                return 2;
+            }
+        };
+
+        match query.select(&document) {
+            Ok(values) => for value in values {
+                if let Err(e) = writeln!(out, "{}", value) {
+                    eprintln!("attr: {}", e);
+                    return 2;
+                }
+            },
+            Err(e) => {
+                eprintln!("attr: {}: document {}: {}", name, i, e.message());
+                code = 1;
+            },
+        }
+    }
+
+    code
+}
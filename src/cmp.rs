@@ -0,0 +1,228 @@
+//! Sorting, grouping and deduplication keyed by paths.
+//!
+//! `by_path` turns a path into a sort key. Keys can be flipped using `desc`
+//! and combined into multi-key orderings using tuples, where later keys
+//! break ties of earlier ones.
+//!
+//! Paths are bound to the lifetime of the data they traverse, so sorting
+//! works on collections of references.
+//!
+//! ```
+//! # use attr::retrieve;
+//! # use attr::cmp::{by_path, sort};
+//! # struct User { name: String, age: u32 }
+//! # struct NameAttribute;
+//! # struct AgeAttribute;
+//! # impl<'a> attr::Attr<&'a User> for NameAttribute { type Output = &'a str; fn get(&self, u: &'a User) -> &'a str { &u.name } fn name(&self) -> &str { "name" } }
+//! # impl<'a> attr::Attr<&'a User> for AgeAttribute { type Output = u32; fn get(&self, u: &'a User) -> u32 { u.age } fn name(&self) -> &str { "age" } }
+//! # fn main() -> attr::Result<()> {
+//! # let users = vec![User { name: "bob".into(), age: 25 }, User { name: "carol".into(), age: 30 }, User { name: "alice".into(), age: 30 }];
+//! let order = (by_path(retrieve(AgeAttribute)).desc(), by_path(retrieve(NameAttribute)));
+//!
+//! let mut users = users.iter().collect::<Vec<_>>();
+//! sort(&mut users, &order)?;
+//! # assert_eq!(users.iter().map(|u| u.name.as_str()).collect::<Vec<_>>(), vec!["alice", "carol", "bob"]);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! None of the functions panic on failing traversals, the error is returned instead.
+
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::marker::PhantomData;
+
+use super::{Traverse, Error, Result};
+
+/// An ordering of values.
+///
+/// Implemented by the keys built through `by_path`, and by tuples of them.
+pub trait Compare<'a, 'b: 'a, X: 'b> {
+    /// The sort key of a value
+    type Key: Ord;
+
+    /// The sort key of `item`, failing if it cannot be traversed.
+    fn key(&'a self, item: X) -> Result<Self::Key>;
+
+    /// Compares two values, failing if one of them cannot be traversed.
+    fn compare(&'a self, left: X, right: X) -> Result<Ordering> {
+        Ok(self.key(left)?.cmp(&self.key(right)?))
+    }
+}
+
+/// A sort key given by a path, see `by_path`.
+pub struct ByPath<P, Y> {
+    path: P,
+    descending: bool,
+    phantom_y: PhantomData<Y>,
+}
+
+/// Orders values by the result of `path`, in ascending order.
+pub fn by_path<P, Y>(path: P) -> ByPath<P, Y> {
+    ByPath { path, descending: false, phantom_y: PhantomData }
+}
+
+impl<P, Y> ByPath<P, Y> {
+    /// Orders in ascending order, which is the default.
+    pub fn asc(mut self) -> ByPath<P, Y> {
+        self.descending = false;
+        self
+    }
+
+    /// Orders in descending order.
+    pub fn desc(mut self) -> ByPath<P, Y> {
+        self.descending = true;
+        self
+    }
+}
+
+/// The key of a single `ByPath`, ordered in its direction.
+#[derive(Debug, Clone)]
+pub struct Directed<Y> {
+    value: Y,
+    descending: bool,
+}
+
+impl<Y> Directed<Y> {
+    /// The value returned by the path
+    pub fn value(&self) -> &Y {
+        &self.value
+    }
+}
+
+impl<Y: Ord> Ord for Directed<Y> {
+    fn cmp(&self, other: &Directed<Y>) -> Ordering {
+        let ordering = self.value.cmp(&other.value);
+        if self.descending { ordering.reverse() } else { ordering }
+    }
+}
+
+impl<Y: Ord> PartialOrd for Directed<Y> {
+    fn partial_cmp(&self, other: &Directed<Y>) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<Y: Ord> PartialEq for Directed<Y> {
+    fn eq(&self, other: &Directed<Y>) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<Y: Ord> Eq for Directed<Y> {}
+
+impl<'a, 'b: 'a, X: 'b, Y: 'b + Ord, P: Traverse<'a, 'b, X, Y>> Compare<'a, 'b, X> for ByPath<P, Y> {
+    type Key = Directed<Y>;
+
+    fn key(&'a self, item: X) -> Result<Directed<Y>> {
+        Ok(Directed { value: self.path.traverse(item)?, descending: self.descending })
+    }
+}
+
+macro_rules! compare_tuple {
+    ($($C:ident $idx:tt),+) => {
+        impl<'a, 'b: 'a, X: 'b + Copy, $($C: Compare<'a, 'b, X>),+> Compare<'a, 'b, X> for ($($C,)+) {
+            type Key = ($($C::Key,)+);
+
+            fn key(&'a self, item: X) -> Result<Self::Key> {
+                Ok(($(self.$idx.key(item)?,)+))
+            }
+
+            fn compare(&'a self, left: X, right: X) -> Result<Ordering> {
+                $(
+                    match self.$idx.compare(left, right)? {
+                        Ordering::Equal => {},
+                        ordering => return Ok(ordering),
+                    }
+                )+
+                Ok(Ordering::Equal)
+            }
+        }
+    }
+}
+
+compare_tuple!(C0 0);
+compare_tuple!(C0 0, C1 1);
+compare_tuple!(C0 0, C1 1, C2 2);
+compare_tuple!(C0 0, C1 1, C2 2, C3 3);
+compare_tuple!(C0 0, C1 1, C2 2, C3 3, C4 4);
+compare_tuple!(C0 0, C1 1, C2 2, C3 3, C4 4, C5 5);
+compare_tuple!(C0 0, C1 1, C2 2, C3 3, C4 4, C5 5, C6 6);
+compare_tuple!(C0 0, C1 1, C2 2, C3 3, C4 4, C5 5, C6 6, C7 7);
+
+/// A comparison function as returned by `comparator`.
+pub type Comparator<'e, X> = Box<dyn FnMut(&X, &X) -> Ordering + 'e>;
+
+/// A comparison function for `sort_by` and friends.
+///
+/// As comparison functions cannot fail, the first error is stored in
+/// `error`. Values whose key cannot be traversed are ordered after all
+/// others, so the order stays total and `sort_by` does not panic. Prefer
+/// `sort` for paths that may fail, which stops at the first error instead.
+///
+/// Keys are traversed on every comparison.
+pub fn comparator<'a: 'e, 'b: 'a, 'e, X: 'b + Copy, C: Compare<'a, 'b, X>>(order: &'a C, error: &'e mut Option<Error>) -> Comparator<'e, X> {
+    let mut key = move |item: &X| order.key(*item).map_err(|e| { error.get_or_insert(e); });
+    Box::new(move |left: &X, right: &X| match (key(left), key(right)) {
+        (Ok(left), Ok(right)) => left.cmp(&right),
+        (Ok(_), Err(())) => Ordering::Less,
+        (Err(()), Ok(_)) => Ordering::Greater,
+        (Err(()), Err(())) => Ordering::Equal,
+    })
+}
+
+/// Sorts `items` by `order`. The sort is stable.
+///
+/// The keys of all items are traversed once, before sorting. Fails with
+/// the first traversal error, with the index of the failing item recorded.
+/// `items` is left untouched in that case.
+pub fn sort<'a, 'b: 'a, X: 'b + Copy, C: Compare<'a, 'b, X>>(items: &mut [X], order: &'a C) -> Result<()> {
+    let mut keyed = items.iter()
+        .enumerate()
+        .map(|(i, item)| order.key(*item).map(|key| (key, *item)).map_err(|e| e.with_index(i)))
+        .collect::<Result<Vec<_>>>()?;
+
+    keyed.sort_by(|left, right| left.0.cmp(&right.0));
+
+    for (slot, (_, item)) in items.iter_mut().zip(keyed) {
+        *slot = item;
+    }
+    Ok(())
+}
+
+/// Groups `items` by the result of `path`, keeping the order of items within a group.
+///
+/// Fails with the first traversal error, with the index of the failing item recorded.
+pub fn group_by_path<'a, 'b: 'a, X: 'b + Copy, Y: 'b + Ord, P, I>(items: I, path: &'a P) -> Result<BTreeMap<Y, Vec<X>>>
+    where P: Traverse<'a, 'b, X, Y>,
+          I: IntoIterator<Item=X>
+{
+    let mut groups = BTreeMap::new();
+    for (i, item) in items.into_iter().enumerate() {
+        let key = path.traverse(item).map_err(|e| e.with_index(i))?;
+        groups.entry(key).or_insert_with(Vec::new).push(item);
+    }
+    Ok(groups)
+}
+
+/// Removes consecutive items for which `path` returns equal values, like `Vec::dedup_by_key`.
+///
+/// Fails with the first traversal error, with the index of the failing item
+/// recorded. `items` is left untouched in that case.
+pub fn dedup_by_path<'a, 'b: 'a, X: 'b + Copy, Y: 'b + PartialEq, P>(items: &mut Vec<X>, path: &'a P) -> Result<()>
+    where P: Traverse<'a, 'b, X, Y>
+{
+    let keys = items.iter()
+        .enumerate()
+        .map(|(i, item)| path.traverse(*item).map_err(|e| e.with_index(i)))
+        .collect::<Result<Vec<Y>>>()?;
+
+    let mut keep = Vec::with_capacity(keys.len());
+    for (i, key) in keys.iter().enumerate() {
+        keep.push(i == 0 || keys[i - 1] != *key);
+    }
+
+    let mut flags = keep.into_iter();
+    items.retain(|_| flags.next().unwrap_or(true));
+    Ok(())
+}
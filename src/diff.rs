@@ -0,0 +1,277 @@
+//! Structural comparison of two values along paths.
+//!
+//! `field` compares the value a path returns, `each` compares the items of
+//! an iterating path one by one. Tuples, vectors and boxes of those compare
+//! along all their paths, so an audit log can report all changes at once.
+//!
+//! ```
+//! # use attr::{retrieve, Attr, IterableAttr};
+//! # use attr::diff::{field, each, Diff};
+//! # #[derive(Clone)] struct User { name: String, data: Data, items: Vec<Item> }
+//! # #[derive(Clone)] struct Data { email: String }
+//! # #[derive(Clone)] struct Item { name: String }
+//! # struct NameAttribute;
+//! # struct DataAttribute;
+//! # struct EmailAttribute;
+//! # struct ItemsAttribute;
+//! # impl<'a> Attr<&'a User> for NameAttribute { type Output = &'a str; fn get(&self, u: &'a User) -> &'a str { &u.name } fn name(&self) -> &str { "name" } }
+//! # impl<'a> Attr<&'a Item> for NameAttribute { type Output = &'a str; fn get(&self, i: &'a Item) -> &'a str { &i.name } fn name(&self) -> &str { "name" } }
+//! # impl<'a> Attr<&'a User> for DataAttribute { type Output = &'a Data; fn get(&self, u: &'a User) -> &'a Data { &u.data } fn name(&self) -> &str { "data" } }
+//! # impl<'a> Attr<&'a Data> for EmailAttribute { type Output = &'a str; fn get(&self, d: &'a Data) -> &'a str { &d.email } fn name(&self) -> &str { "email" } }
+//! # impl<'a> Attr<&'a User> for ItemsAttribute { type Output = &'a [Item]; fn get(&self, u: &'a User) -> &'a [Item] { &u.items } fn name(&self) -> &str { "items" } }
+//! # impl<'a> IterableAttr<'a, &'a User> for ItemsAttribute { type Item = &'a Item; fn iter(&self, u: &'a User) -> Box<dyn Iterator<Item=&'a Item> + 'a> { Box::new(u.items.iter()) } }
+//! # fn main() -> attr::Result<()> {
+//! # let before = User { name: "Flo".into(), data: Data { email: "a@example.com".into() }, items: vec![] };
+//! # let mut after = before.clone();
+//! # after.data.email = "b@example.com".into();
+//! let paths = (
+//!     field(retrieve(NameAttribute)),
+//!     field(retrieve(EmailAttribute).from(DataAttribute)),
+//!     each(retrieve(NameAttribute).mapped(ItemsAttribute)),
+//! );
+//!
+//! for change in paths.diff(&before, &after)? {
+//!     println!("{}", change);
+//! #   assert_eq!(change.to_string(), "data.email changed from \"a@example.com\" to \"b@example.com\"");
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! This prints `data.email changed from "a@example.com" to "b@example.com"`.
+//!
+//! With the `serde` feature, `all` compares every field of two `Serialize`
+//! values instead, without listing paths.
+//!
+//! Values are rendered using their `Debug` implementation. This is intended:
+//! strings are quoted, so empty strings and surrounding whitespace remain
+//! visible in the log.
+
+use std::fmt;
+use std::marker::PhantomData;
+
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+use super::{Traverse, PathInfo, Result};
+#[cfg(feature = "serde")]
+use super::value::{to_value, Value};
+
+/// A change of the value at a path.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Change {
+    /// The path to the changed value, like `items[2].name`
+    pub path: String,
+    /// The previous value, `None` if it was absent
+    pub old: Option<String>,
+    /// The current value, `None` if it is absent
+    pub new: Option<String>,
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match (&self.old, &self.new) {
+            (Some(old), Some(new)) => write!(f, "{} changed from {} to {}", self.path, old, new),
+            (None, Some(new)) => write!(f, "{} was set to {}", self.path, new),
+            (Some(old), None) => write!(f, "{} was removed, it was {}", self.path, old),
+            (None, None) => write!(f, "{} changed", self.path),
+        }
+    }
+}
+
+/// A set of paths along which to compare values.
+///
+/// Implemented by the paths built through `field` and `each`, and by tuples,
+/// vectors and boxes of them.
+pub trait Diff<'a, 'b: 'a, X: 'b> {
+    /// Compares `old` and `new`, adding all changes to `changes`.
+    ///
+    /// Absent values, signalled by `ErrorKind::Missing`, are recorded as such.
+    /// Other traversal errors are returned.
+    fn diff_into(&'a self, old: X, new: X, changes: &mut Vec<Change>) -> Result<()>;
+
+    /// Compares `old` and `new`, returning all changes in order of the paths.
+    fn diff(&'a self, old: X, new: X) -> Result<Vec<Change>> {
+        let mut changes = Vec::new();
+        self.diff_into(old, new, &mut changes)?;
+        Ok(changes)
+    }
+}
+
+/// A path compared as a single value, see `field`.
+pub struct Field<P, Y> {
+    path: P,
+    phantom_y: PhantomData<Y>,
+}
+
+/// An iterating path compared item by item, see `each`.
+pub struct Each<P, Y> {
+    path: P,
+    phantom_y: PhantomData<Y>,
+}
+
+/// Compares the value returned by `path`.
+pub fn field<P, Y>(path: P) -> Field<P, Y> {
+    Field { path, phantom_y: PhantomData }
+}
+
+/// Compares all fields of two values, see `all`.
+#[cfg(feature = "serde")]
+pub struct All;
+
+/// Compares all fields of two `Serialize` values, and the elements of
+/// sequences by position, as serialized into `Value`s.
+///
+/// Paths are named after the serialized fields, like `tags[1].label`, and
+/// changes are reported in alphabetical order of the fields. `None` and other
+/// values serialized as `Value::Null` are reported as absent.
+#[cfg(feature = "serde")]
+pub fn all() -> All {
+    All
+}
+
+/// Compares the values returned by an iterating `path`, such as a `MapPath`, by position.
+///
+/// Items only present on one side are reported as added or removed.
+pub fn each<P, Y>(path: P) -> Each<P, Y> {
+    Each { path, phantom_y: PhantomData }
+}
+
+fn present<Y>(result: Result<Y>) -> Result<Option<Y>> {
+    match result {
+        Ok(value) => Ok(Some(value)),
+        Err(ref e) if e.is_missing() => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+fn compare<Y: PartialEq + fmt::Debug>(path: String, old: Option<Y>, new: Option<Y>, changes: &mut Vec<Change>) {
+    if old != new {
+        changes.push(Change {
+            path,
+            old: old.map(|v| format!("{:?}", v)),
+            new: new.map(|v| format!("{:?}", v)),
+        });
+    }
+}
+
+impl<'a, 'b: 'a, X: 'b, Y: 'b + PartialEq + fmt::Debug, P: Traverse<'a, 'b, X, Y> + PathInfo> Diff<'a, 'b, X> for Field<P, Y> {
+    fn diff_into(&'a self, old: X, new: X, changes: &mut Vec<Change>) -> Result<()> {
+        let old = present(self.path.traverse(old))?;
+        let new = present(self.path.traverse(new))?;
+        compare(self.path.breadcrumb(None), old, new, changes);
+        Ok(())
+    }
+}
+
+impl<'a, X: 'a, Y: 'a + PartialEq + fmt::Debug, P> Diff<'a, 'a, X> for Each<P, Y>
+    where P: Traverse<'a, 'a, X, Box<dyn Iterator<Item=Result<Y>> + 'a>> + PathInfo
+{
+    fn diff_into(&'a self, old: X, new: X, changes: &mut Vec<Change>) -> Result<()> {
+        let mut old = present(self.path.traverse(old))?.into_iter().flatten();
+        let mut new = present(self.path.traverse(new))?.into_iter().flatten();
+
+        let mut i = 0;
+        loop {
+            let (o, n) = match (old.next(), new.next()) {
+                (None, None) => return Ok(()),
+                (o, n) => (o, n),
+            };
+            let o = o.map_or(Ok(None), present).map_err(|e| e.with_index(i))?;
+            let n = n.map_or(Ok(None), present).map_err(|e| e.with_index(i))?;
+            compare(self.path.breadcrumb(Some(i)), o, n, changes);
+            i += 1;
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'a, 'b: 'a, T: Serialize + ?Sized + 'b> Diff<'a, 'b, &'b T> for All {
+    fn diff_into(&'a self, old: &'b T, new: &'b T, changes: &mut Vec<Change>) -> Result<()> {
+        compare_values(String::new(), Some(&to_value(old)?), Some(&to_value(new)?), changes);
+        Ok(())
+    }
+}
+
+#[cfg(feature = "serde")]
+fn compare_values(path: String, old: Option<&Value>, new: Option<&Value>, changes: &mut Vec<Change>) {
+    let member = |name: &str| if path.is_empty() { name.to_owned() } else { format!("{}.{}", path, name) };
+
+    match (old, new) {
+        (Some(Value::Map(old)), Some(Value::Map(new))) => {
+            for (name, value) in old {
+                compare_values(member(name), Some(value), new.get(name), changes);
+            }
+            for (name, value) in new.iter().filter(|&(name, _)| !old.contains_key(name)) {
+                compare_values(member(name), None, Some(value), changes);
+            }
+        },
+        (Some(Value::List(old)), Some(Value::List(new))) => {
+            for i in 0..old.len().max(new.len()) {
+                compare_values(format!("{}[{}]", path, i), old.get(i), new.get(i), changes);
+            }
+        },
+        (old, new) => compare(path, rendered(old), rendered(new), changes),
+    }
+}
+
+#[cfg(feature = "serde")]
+fn rendered(value: Option<&Value>) -> Option<Rendered<'_>> {
+    value.filter(|v| **v != Value::Null).map(Rendered)
+}
+
+/// Renders a `Value` like the `Debug` output of the serialized type.
+#[cfg(feature = "serde")]
+#[derive(PartialEq)]
+struct Rendered<'v>(&'v Value);
+
+#[cfg(feature = "serde")]
+impl<'v> fmt::Debug for Rendered<'v> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self.0 {
+            Value::Null => f.write_str("None"),
+            Value::Bool(b) => write!(f, "{:?}", b),
+            Value::I64(n) => write!(f, "{:?}", n),
+            Value::U64(n) => write!(f, "{:?}", n),
+            Value::F64(n) => write!(f, "{:?}", n),
+            Value::String(ref s) => write!(f, "{:?}", s),
+            Value::List(ref l) => f.debug_list().entries(l.iter().map(Rendered)).finish(),
+            Value::Map(ref m) => f.debug_map().entries(m.iter().map(|(k, v)| (k, Rendered(v)))).finish(),
+        }
+    }
+}
+
+impl<'a, 'b: 'a, X: 'b + Copy, D: Diff<'a, 'b, X>> Diff<'a, 'b, X> for Vec<D> {
+    fn diff_into(&'a self, old: X, new: X, changes: &mut Vec<Change>) -> Result<()> {
+        for d in self {
+            d.diff_into(old, new, changes)?;
+        }
+        Ok(())
+    }
+}
+
+impl<'a, 'b: 'a, X: 'b, D: Diff<'a, 'b, X> + ?Sized> Diff<'a, 'b, X> for Box<D> {
+    fn diff_into(&'a self, old: X, new: X, changes: &mut Vec<Change>) -> Result<()> {
+        (**self).diff_into(old, new, changes)
+    }
+}
+
+macro_rules! diff_tuple {
+    ($($D:ident $idx:tt),+) => {
+        impl<'a, 'b: 'a, X: 'b + Copy, $($D: Diff<'a, 'b, X>),+> Diff<'a, 'b, X> for ($($D,)+) {
+            fn diff_into(&'a self, old: X, new: X, changes: &mut Vec<Change>) -> Result<()> {
+                $(self.$idx.diff_into(old, new, changes)?;)+
+                Ok(())
+            }
+        }
+    }
+}
+
+diff_tuple!(D0 0);
+diff_tuple!(D0 0, D1 1);
+diff_tuple!(D0 0, D1 1, D2 2);
+diff_tuple!(D0 0, D1 1, D2 2, D3 3);
+diff_tuple!(D0 0, D1 1, D2 2, D3 3, D4 4);
+diff_tuple!(D0 0, D1 1, D2 2, D3 3, D4 4, D5 5);
+diff_tuple!(D0 0, D1 1, D2 2, D3 3, D4 4, D5 5, D6 6);
+diff_tuple!(D0 0, D1 1, D2 2, D3 3, D4 4, D5 5, D6 6, D7 7);
@@ -0,0 +1,295 @@
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::fmt;
+use std::marker::PhantomData;
+use std::rc::Rc;
+
+use super::{Attr, InsecureAttr, IterableAttr, Traverse, PathInfo, PathDescriptor, Segment, SegmentKind, Error, Result};
+
+/// A value retrieved through a `DynPath`.
+pub enum AnyValue<'a> {
+    /// A reference into the traversed data
+    Borrowed(&'a dyn Any),
+    /// A value computed by an attribute, for example a number returned by value
+    Owned(Box<dyn Any>),
+}
+
+impl<'a> AnyValue<'a> {
+    /// A reference to the value, if it is of type `T`
+    pub fn downcast_ref<T: Any>(&self) -> Option<&T> {
+        match *self {
+            AnyValue::Borrowed(v) => v.downcast_ref(),
+            AnyValue::Owned(ref v) => v.downcast_ref(),
+        }
+    }
+
+    /// Whether the value is of type `T`
+    pub fn is<T: Any>(&self) -> bool {
+        self.downcast_ref::<T>().is_some()
+    }
+}
+
+impl<'a> fmt::Debug for AnyValue<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            AnyValue::Borrowed(_) => f.write_str("AnyValue::Borrowed(..)"),
+            AnyValue::Owned(_) => f.write_str("AnyValue::Owned(..)"),
+        }
+    }
+}
+
+/// Conversion of attribute outputs into `AnyValue`s.
+///
+/// References are kept as references. Values returned by value and
+/// string slices are stored as owned values, string slices as `String`.
+pub trait IntoAny<'a> {
+    /// Performs the conversion
+    fn into_any(self) -> AnyValue<'a>;
+}
+
+impl<'a, T: Any> IntoAny<'a> for &'a T {
+    fn into_any(self) -> AnyValue<'a> {
+        AnyValue::Borrowed(self)
+    }
+}
+
+impl<'a> IntoAny<'a> for &'a str {
+    fn into_any(self) -> AnyValue<'a> {
+        AnyValue::Owned(Box::new(self.to_owned()))
+    }
+}
+
+macro_rules! into_any_owned {
+    ($($t:ty),*) => {
+        $(
+            impl<'a> IntoAny<'a> for $t {
+                fn into_any(self) -> AnyValue<'a> {
+                    AnyValue::Owned(Box::new(self))
+                }
+            }
+        )*
+    }
+}
+
+into_any_owned!(bool, char, String, i8, i16, i32, i64, isize, u8, u16, u32, u64, usize, f32, f64);
+
+/// Type-erased attribute, stored in a `Registry`.
+trait ErasedAttr {
+    fn kind(&self) -> SegmentKind;
+    fn input(&self) -> TypeId;
+    fn apply<'a>(&self, value: &'a dyn Any) -> Result<Vec<AnyValue<'a>>>;
+}
+
+struct Erased<T, A, K> {
+    attr: A,
+    phantom_t: PhantomData<fn(&T)>,
+    phantom_k: PhantomData<K>,
+}
+
+struct Plain;
+struct Insecure;
+struct Iterable;
+
+fn downcast<T: Any>(value: &dyn Any) -> Result<&T> {
+    value.downcast_ref::<T>().ok_or_else(|| Error::invalid("value is not of the type the attribute expects"))
+}
+
+impl<T: Any, A> ErasedAttr for Erased<T, A, Plain>
+    where A: for<'a> Attr<&'a T>,
+          for<'a> <A as Attr<&'a T>>::Output: IntoAny<'a>
+{
+    fn kind(&self) -> SegmentKind {
+        SegmentKind::Attr
+    }
+
+    fn input(&self) -> TypeId {
+        TypeId::of::<T>()
+    }
+
+    fn apply<'a>(&self, value: &'a dyn Any) -> Result<Vec<AnyValue<'a>>> {
+        let value = downcast::<T>(value)?;
+        Ok(vec![self.attr.get(value).into_any()])
+    }
+}
+
+impl<T: Any, A> ErasedAttr for Erased<T, A, Insecure>
+    where A: for<'a> InsecureAttr<&'a T>,
+          for<'a> <A as InsecureAttr<&'a T>>::Output: IntoAny<'a>
+{
+    fn kind(&self) -> SegmentKind {
+        SegmentKind::Insecure
+    }
+
+    fn input(&self) -> TypeId {
+        TypeId::of::<T>()
+    }
+
+    fn apply<'a>(&self, value: &'a dyn Any) -> Result<Vec<AnyValue<'a>>> {
+        let value = downcast::<T>(value)?;
+        Ok(vec![self.attr.get(value)?.into_any()])
+    }
+}
+
+impl<T: Any, A> ErasedAttr for Erased<T, A, Iterable>
+    where A: for<'a> IterableAttr<'a, &'a T>,
+          for<'a> <A as IterableAttr<'a, &'a T>>::Item: IntoAny<'a>
+{
+    fn kind(&self) -> SegmentKind {
+        SegmentKind::Iterable
+    }
+
+    fn input(&self) -> TypeId {
+        TypeId::of::<T>()
+    }
+
+    fn apply<'a>(&self, value: &'a dyn Any) -> Result<Vec<AnyValue<'a>>> {
+        let value = downcast::<T>(value)?;
+        Ok(self.attr.iter(value).map(IntoAny::into_any).collect())
+    }
+}
+
+/// A set of named attributes, used to turn `PathDescriptor`s back into paths.
+///
+/// Several attributes may share a name, as long as they work on different types.
+/// The attribute to use is picked by the type of the value at hand during traversal.
+#[derive(Default)]
+pub struct Registry {
+    attrs: HashMap<String, Vec<Rc<dyn ErasedAttr>>>,
+}
+
+impl Registry {
+    /// Creates an empty registry.
+    pub fn new() -> Registry {
+        Registry::default()
+    }
+
+    fn insert<N: Into<String>>(&mut self, name: N, attr: Rc<dyn ErasedAttr>) -> &mut Registry {
+        self.attrs.entry(name.into()).or_default().push(attr);
+        self
+    }
+
+    /// Registers an attribute that always succeeds.
+    pub fn register<T: Any, A>(&mut self, attr: A) -> &mut Registry
+        where A: 'static + for<'a> Attr<&'a T>,
+              for<'a> <A as Attr<&'a T>>::Output: IntoAny<'a>
+    {
+        let name = attr.name().to_owned();
+        self.insert(name, Rc::new(Erased { attr, phantom_t: PhantomData::<fn(&T)>, phantom_k: PhantomData::<Plain> }))
+    }
+
+    /// Registers an attribute that may fail.
+    pub fn register_insecure<T: Any, A>(&mut self, attr: A) -> &mut Registry
+        where A: 'static + for<'a> InsecureAttr<&'a T>,
+              for<'a> <A as InsecureAttr<&'a T>>::Output: IntoAny<'a>
+    {
+        let name = attr.name().to_owned();
+        self.insert(name, Rc::new(Erased { attr, phantom_t: PhantomData::<fn(&T)>, phantom_k: PhantomData::<Insecure> }))
+    }
+
+    /// Registers an attribute that can be iterated.
+    pub fn register_iterable<T: Any, A>(&mut self, attr: A) -> &mut Registry
+        where A: 'static + for<'a> IterableAttr<'a, &'a T>,
+              for<'a> <A as IterableAttr<'a, &'a T>>::Item: IntoAny<'a>
+    {
+        let name = Attr::name(&attr).to_owned();
+        self.insert(name, Rc::new(Erased { attr, phantom_t: PhantomData::<fn(&T)>, phantom_k: PhantomData::<Iterable> }))
+    }
+
+    /// The kinds of the attributes registered under `name`.
+    pub fn kinds(&self, name: &str) -> Vec<SegmentKind> {
+        let mut kinds = Vec::new();
+        for attr in self.attrs.get(name).into_iter().flatten() {
+            if !kinds.contains(&attr.kind()) {
+                kinds.push(attr.kind());
+            }
+        }
+        kinds
+    }
+
+    /// Turns a descriptor into a path, using the attributes of this registry.
+    ///
+    /// Fails if an attribute of the given name and kind is not registered.
+    pub fn resolve(&self, descriptor: &PathDescriptor) -> Result<DynPath> {
+        let mut segments = Vec::new();
+
+        for segment in &descriptor.segments {
+            let candidates = self.attrs.get(&segment.name)
+                .map(|attrs| attrs.iter().filter(|a| a.kind() == segment.kind).cloned().collect::<Vec<_>>())
+                .unwrap_or_default();
+
+            if candidates.is_empty() {
+                return Err(Error::invalid(format!("no attribute {} of kind {:?} is registered", segment.name, segment.kind)));
+            }
+
+            segments.push(DynSegment { name: segment.name.clone(), kind: segment.kind, candidates });
+        }
+
+        Ok(DynPath { segments })
+    }
+}
+
+struct DynSegment {
+    name: String,
+    kind: SegmentKind,
+    candidates: Vec<Rc<dyn ErasedAttr>>,
+}
+
+/// A path assembled at runtime, for example by `Registry::resolve`.
+///
+/// Traversal returns all values reached, as `AnyValue`s. Paths without
+/// iterating segments return exactly one value.
+pub struct DynPath {
+    segments: Vec<DynSegment>,
+}
+
+impl DynPath {
+    /// Traverses the path on a type-erased value.
+    pub fn traverse_any<'a>(&self, root: &'a dyn Any) -> Result<Vec<AnyValue<'a>>> {
+        self.traverse_located(root).map_err(|(_, e)| e)
+    }
+
+    /// Like `traverse_any`, but failures carry the position of the failing segment.
+    pub(crate) fn traverse_located<'a>(&self, root: &'a dyn Any) -> std::result::Result<Vec<AnyValue<'a>>, (usize, Error)> {
+        let mut values = vec![AnyValue::Borrowed(root)];
+
+        for (i, segment) in self.segments.iter().enumerate() {
+            let mut next = Vec::new();
+
+            for value in values {
+                let value = match value {
+                    AnyValue::Borrowed(v) => v,
+                    AnyValue::Owned(_) => return Err((i, Error::invalid(format!("cannot retrieve {} from a computed value", segment.name)))),
+                };
+
+                let attr = segment.candidates.iter()
+                    .find(|a| a.input() == value.type_id())
+                    .ok_or_else(|| (i, Error::invalid(format!("{} is not an attribute of the value at hand", segment.name))))?;
+
+                next.extend(attr.apply(value).map_err(|e| (i, e))?);
+            }
+
+            values = next;
+        }
+
+        Ok(values)
+    }
+}
+
+impl<'a, 'b: 'a, T: Any> Traverse<'a, 'b, &'b T, Vec<AnyValue<'b>>> for DynPath {
+    #[inline]
+    fn traverse(&'a self, obj: &'b T) -> Result<Vec<AnyValue<'b>>> {
+        self.traverse_any(obj)
+    }
+}
+
+impl PathInfo for DynPath {
+    fn push_segments<'a>(&'a self, segments: &mut Vec<Segment<'a>>) {
+        segments.extend(self.segments.iter().map(|s| Segment { name: &s.name, kind: s.kind }));
+    }
+}
+
+impl fmt::Display for DynPath {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.descriptor().fmt(f)
+    }
+}
@@ -0,0 +1,163 @@
+//! Loading values from environment variables.
+//!
+//! `Bindings` list the attributes that can be set, as mutable attributes
+//! returning references. Values of nested structures are bound through
+//! the bindings of the nested type. Variables are mapped onto attributes
+//! by name: with the prefix `APP_`, `APP_DATA__EMAIL` sets the `email`
+//! attribute of the `data` attribute. Values are parsed through `FromStr`.
+//!
+//! ```
+//! # use attr::Attr;
+//! # use attr::env::{self, Bind, Bindings};
+//! # #[derive(Default)] struct Config { port: u16, data: Data }
+//! # #[derive(Default)] struct Data { email: String }
+//! # struct PortAttribute;
+//! # struct DataAttribute;
+//! # struct EmailAttribute;
+//! # impl<'a> Attr<&'a mut Config> for PortAttribute { type Output = &'a mut u16; fn get(&self, c: &'a mut Config) -> &'a mut u16 { &mut c.port } fn name(&self) -> &str { "port" } }
+//! # impl<'a> Attr<&'a mut Config> for DataAttribute { type Output = &'a mut Data; fn get(&self, c: &'a mut Config) -> &'a mut Data { &mut c.data } fn name(&self) -> &str { "data" } }
+//! # impl<'a> Attr<&'a mut Data> for EmailAttribute { type Output = &'a mut String; fn get(&self, d: &'a mut Data) -> &'a mut String { &mut d.email } fn name(&self) -> &str { "email" } }
+//! impl Bind for Config {
+//!     fn bindings() -> Bindings<Config> {
+//!         Bindings::new()
+//!             .field(PortAttribute)
+//!             .nested(DataAttribute, Bindings::new().field(EmailAttribute))
+//!     }
+//! }
+//!
+//! # fn main() -> Result<(), attr::validate::Report> {
+//! # std::env::set_var("APP_DATA__EMAIL", "flo@example.com");
+//! # let mut config = Config::default();
+//! env::load_into(&mut config, "APP_")?;
+//! # assert_eq!(config.data.email, "flo@example.com");
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Loading does not stop at the first problem. Variables with the prefix
+//! that match no attribute or cannot be parsed are reported by path in a
+//! `validate::Report`, all other variables are applied.
+
+use std::env;
+use std::fmt;
+use std::rc::Rc;
+use std::str::FromStr;
+
+use super::Attr;
+use super::validate::Report;
+
+type Setter<T> = Rc<dyn Fn(&mut T, &str) -> std::result::Result<(), String>>;
+
+struct Binding<T: ?Sized> {
+    names: Vec<String>,
+    set: Setter<T>,
+}
+
+/// The attributes of a type that can be set from environment variables.
+pub struct Bindings<T: ?Sized> {
+    bindings: Vec<Binding<T>>,
+}
+
+impl<T: ?Sized> Default for Bindings<T> {
+    fn default() -> Bindings<T> {
+        Bindings { bindings: Vec::new() }
+    }
+}
+
+impl<T: ?Sized + 'static> Bindings<T> {
+    /// Creates empty bindings.
+    pub fn new() -> Bindings<T> {
+        Bindings::default()
+    }
+
+    /// Binds an attribute, which is set to the parsed value.
+    pub fn field<A, Y>(mut self, attr: A) -> Bindings<T>
+        where A: for<'x> Attr<&'x mut T, Output=&'x mut Y> + 'static,
+              Y: FromStr + 'static,
+              Y::Err: fmt::Display
+    {
+        let name = attr.name().to_owned();
+        let set = move |target: &mut T, value: &str| {
+            *attr.get(target) = value.parse().map_err(|e: Y::Err| e.to_string())?;
+            Ok(())
+        };
+        self.bindings.push(Binding { names: vec![name], set: Rc::new(set) });
+        self
+    }
+
+    /// Binds an optional attribute, which is set to `Some` of the parsed value.
+    pub fn optional<A, Y>(mut self, attr: A) -> Bindings<T>
+        where A: for<'x> Attr<&'x mut T, Output=&'x mut Option<Y>> + 'static,
+              Y: FromStr + 'static,
+              Y::Err: fmt::Display
+    {
+        let name = attr.name().to_owned();
+        let set = move |target: &mut T, value: &str| {
+            *attr.get(target) = Some(value.parse().map_err(|e: Y::Err| e.to_string())?);
+            Ok(())
+        };
+        self.bindings.push(Binding { names: vec![name], set: Rc::new(set) });
+        self
+    }
+
+    /// Binds the attributes of a nested value.
+    pub fn nested<A, U>(mut self, attr: A, nested: Bindings<U>) -> Bindings<T>
+        where A: for<'x> Attr<&'x mut T, Output=&'x mut U> + 'static,
+              U: ?Sized + 'static
+    {
+        let attr = Rc::new(attr);
+
+        for binding in nested.bindings {
+            let mut names = vec![attr.name().to_owned()];
+            names.extend(binding.names);
+
+            let attr = attr.clone();
+            let inner = binding.set;
+            let set = move |target: &mut T, value: &str| inner(attr.get(target), value);
+            self.bindings.push(Binding { names, set: Rc::new(set) });
+        }
+        self
+    }
+
+    /// Applies all `vars` starting with `prefix` to `target`.
+    ///
+    /// Variables are applied in order of their names.
+    pub fn load<I>(&self, target: &mut T, prefix: &str, vars: I) -> std::result::Result<(), Report>
+        where I: IntoIterator<Item=(String, String)>
+    {
+        let mut vars = vars.into_iter().filter(|(key, _)| key.starts_with(prefix)).collect::<Vec<_>>();
+        vars.sort();
+
+        let mut report = Report::new();
+
+        for (key, value) in vars {
+            let rest = &key[prefix.len()..];
+            let binding = self.bindings.iter().find(|b| variable(&b.names) == rest);
+
+            match binding {
+                Some(binding) => if let Err(message) = (binding.set)(target, &value) {
+                    report.fail(binding.names.join("."), format!("{}: {}", key, message));
+                },
+                None => report.fail(rest.to_lowercase().replace("__", "."), format!("{} does not match any attribute", key)),
+            }
+        }
+
+        report.into_result()
+    }
+}
+
+fn variable(names: &[String]) -> String {
+    names.iter().map(|n| n.to_uppercase()).collect::<Vec<_>>().join("__")
+}
+
+/// Types with bindings for environment variables.
+pub trait Bind {
+    /// The attributes that can be set
+    fn bindings() -> Bindings<Self>;
+}
+
+/// Applies all environment variables starting with `prefix` to `target`.
+pub fn load_into<T: Bind + ?Sized + 'static>(target: &mut T, prefix: &str) -> std::result::Result<(), Report> {
+    let vars = env::vars_os().map(|(k, v)| (k.to_string_lossy().into_owned(), v.to_string_lossy().into_owned()));
+    T::bindings().load(target, prefix, vars)
+}
@@ -0,0 +1,102 @@
+use std::error;
+use std::fmt;
+
+/// The kind of failure that occured during a traversal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    /// The requested value is not present, for example a missing key.
+    Missing,
+    /// The data does not have the expected shape, for example
+    /// a number where an object was expected.
+    Invalid,
+}
+
+/// The error returned by failed retrievals and traversals.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Error {
+    kind: ErrorKind,
+    message: String,
+    index: Option<usize>,
+}
+
+impl Error {
+    /// Creates an error of the given kind.
+    pub fn new<M: Into<String>>(kind: ErrorKind, message: M) -> Error {
+        Error { kind, message: message.into(), index: None }
+    }
+
+    /// Creates an error signalling that the requested value is not present.
+    pub fn missing<M: Into<String>>(message: M) -> Error {
+        Error::new(ErrorKind::Missing, message)
+    }
+
+    /// Creates an error signalling that the data is malformed.
+    pub fn invalid<M: Into<String>>(message: M) -> Error {
+        Error::new(ErrorKind::Invalid, message)
+    }
+
+    /// The kind of the error
+    pub fn kind(&self) -> ErrorKind {
+        self.kind
+    }
+
+    /// Whether the error signals an absent value
+    pub fn is_missing(&self) -> bool {
+        self.kind == ErrorKind::Missing
+    }
+
+    /// The error message
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Records the position of the failing item in an iteration.
+    pub fn with_index(mut self, index: usize) -> Error {
+        self.index = Some(index);
+        self
+    }
+
+    /// Prefixes the message with `context`, keeping kind and index.
+    pub fn context<C: fmt::Display>(mut self, context: C) -> Error {
+        self.message = format!("{}: {}", context, self.message);
+        self
+    }
+
+    /// The position of the failing item, if the error occured during iteration
+    pub fn index(&self) -> Option<usize> {
+        self.index
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.index {
+            Some(index) => write!(f, "at index {}: {}", index, self.message),
+            None => f.write_str(&self.message),
+        }
+    }
+}
+
+impl error::Error for Error {}
+
+/// Plain messages are treated as `ErrorKind::Invalid`.
+impl From<String> for Error {
+    fn from(message: String) -> Error {
+        Error::invalid(message)
+    }
+}
+
+/// Plain messages are treated as `ErrorKind::Invalid`.
+impl<'a> From<&'a str> for Error {
+    fn from(message: &'a str) -> Error {
+        Error::invalid(message)
+    }
+}
+
+/// Errors raised by `Serialize` implementations are treated as `ErrorKind::Invalid`.
+#[cfg(feature = "serde")]
+impl ::serde::ser::Error for Error {
+    fn custom<T: fmt::Display>(message: T) -> Error {
+        Error::invalid(message.to_string())
+    }
+}
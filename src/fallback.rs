@@ -0,0 +1,110 @@
+use super::{InsecureAttr, InsecurePath, Traverse, SecureTraverse, PathInfo, Segment, Result};
+
+/// A path that tries an alternative path when the value of the first
+/// one is absent.
+///
+/// Only failures of kind `ErrorKind::Missing` fall through to the
+/// alternative. Malformed data, signalled by `ErrorKind::Invalid`, is
+/// returned instead of being hidden by the alternative.
+///
+/// Both paths need to work on the same input. As the input is handed to
+/// both of them, it is passed by shared reference.
+///
+/// Paths are usually inferred and should not be directly used
+/// in user code.
+pub struct FallbackPath<First, Second> {
+    first: First,
+    second: Second,
+}
+
+/// A path that returns a fixed value when the wrapped path fails.
+///
+/// Traversing a `DefaultPath` never fails, `SecureTraverse::get` returns
+/// the value directly. As there is no way to report them, failures of
+/// all kinds are replaced by the value, including malformed data. Use
+/// `optional` to only replace absent values.
+///
+/// Paths are usually inferred and should not be directly used
+/// in user code.
+pub struct DefaultPath<P, Y> {
+    path: P,
+    value: Y,
+}
+
+/// Returns the result of `alternative` if `result` signals an absent value.
+pub(crate) fn fall_back<Y, F: FnOnce() -> Result<Y>>(result: Result<Y>, alternative: F) -> Result<Y> {
+    match result {
+        Err(ref e) if e.is_missing() => alternative(),
+        result => result,
+    }
+}
+
+impl<'a, 'b: 'a, X: 'b + ?Sized, Y: 'b, P: Traverse<'a, 'b, &'b X, Y>, Q: Traverse<'a, 'b, &'b X, Y>> Traverse<'a, 'b, &'b X, Y> for FallbackPath<P, Q> {
+    #[inline]
+    fn traverse(&'a self, obj: &'b X) -> Result<Y> {
+        fall_back(self.first.traverse(obj), || self.second.traverse(obj))
+    }
+}
+
+impl<'a, 'b: 'a, X: 'b, Y: 'b + Clone, P: Traverse<'a, 'b, X, Y>> SecureTraverse<'a, 'b, X, Y> for DefaultPath<P, Y> {
+    #[inline]
+    fn get(&'a self, obj: X) -> Y {
+        self.path.traverse(obj).unwrap_or_else(|_| self.value.clone())
+    }
+}
+
+impl<'a, 'b: 'a, X: 'b, Y: 'b + Clone, P: Traverse<'a, 'b, X, Y>> Traverse<'a, 'b, X, Y> for DefaultPath<P, Y> {
+    #[inline]
+    fn traverse(&'a self, obj: X) -> Result<Y> {
+        Ok(self.get(obj))
+    }
+}
+
+/// Described by the first path, the alternatives are not part of the description.
+impl<P: PathInfo, Q> PathInfo for FallbackPath<P, Q> {
+    fn push_segments<'a>(&'a self, segments: &mut Vec<Segment<'a>>) {
+        self.first.push_segments(segments);
+    }
+}
+
+impl<P: PathInfo, Y> PathInfo for DefaultPath<P, Y> {
+    fn push_segments<'a>(&'a self, segments: &mut Vec<Segment<'a>>) {
+        self.path.push_segments(segments);
+    }
+}
+
+impl<X, Z, A: InsecureAttr<X>, R> InsecurePath<X, Z, A, R> {
+    /// Tries `other` if the value of this path is absent.
+    pub fn or<Q>(self, other: Q) -> FallbackPath<Self, Q> {
+        FallbackPath { first: self, second: other }
+    }
+
+    /// Returns `value` if this path fails for any reason.
+    pub fn or_value(self, value: Z) -> DefaultPath<Self, Z> {
+        DefaultPath { path: self, value }
+    }
+
+    /// Returns the default value of the output type if this path fails for any reason.
+    pub fn or_default(self) -> DefaultPath<Self, Z>
+        where Z: Default
+    {
+        self.or_value(Z::default())
+    }
+}
+
+impl<P, Q> FallbackPath<P, Q> {
+    /// Tries `other` if the values of both previous paths are absent.
+    pub fn or<O>(self, other: O) -> FallbackPath<Self, O> {
+        FallbackPath { first: self, second: other }
+    }
+
+    /// Returns `value` if all paths fail for any reason.
+    pub fn or_value<Y>(self, value: Y) -> DefaultPath<Self, Y> {
+        DefaultPath { path: self, value }
+    }
+
+    /// Returns the default value of the output type if all paths fail for any reason.
+    pub fn or_default<Y: Default>(self) -> DefaultPath<Self, Y> {
+        self.or_value(Y::default())
+    }
+}
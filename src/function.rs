@@ -0,0 +1,165 @@
+use std::marker::PhantomData;
+
+use super::{Attr, InsecureAttr, Path, InsecurePath, Traverse, PathInfo, Segment, Result};
+
+/// An attribute backed by a named function.
+///
+/// Constructed through `attr_fn`.
+pub struct FnAttr<F> {
+    name: &'static str,
+    f: F,
+}
+
+/// A possibly failing attribute backed by a named function.
+///
+/// Constructed through `insecure_attr_fn`.
+pub struct InsecureFnAttr<F> {
+    name: &'static str,
+    f: F,
+}
+
+/// `attr_fn` turns a function into an attribute, so that derived
+/// values can be used as segments of a path.
+pub fn attr_fn<X, Y, F: Fn(X) -> Y>(name: &'static str, f: F) -> FnAttr<F> {
+    FnAttr { name, f }
+}
+
+/// `insecure_attr_fn` turns a function returning a `Result` into
+/// an attribute whose retrieval may fail.
+pub fn insecure_attr_fn<X, Y, F: Fn(X) -> Result<Y>>(name: &'static str, f: F) -> InsecureFnAttr<F> {
+    InsecureFnAttr { name, f }
+}
+
+impl<X, Y, F: Fn(X) -> Y> Attr<X> for FnAttr<F> {
+    type Output = Y;
+
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn get(&self, i: X) -> Y {
+        (self.f)(i)
+    }
+}
+
+impl<X, Y, F: Fn(X) -> Result<Y>> InsecureAttr<X> for InsecureFnAttr<F> {
+    type Output = Y;
+
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn get(&self, i: X) -> Result<Y> {
+        (self.f)(i)
+    }
+}
+
+/// A path whose result is passed through a function.
+///
+/// Paths are usually inferred and should not be directly used
+/// in user code.
+pub struct FnPath<P, Y, F> {
+    path: P,
+    f: F,
+    phantom_y: PhantomData<Y>,
+}
+
+/// A path whose result is passed through a function that may fail.
+///
+/// Paths are usually inferred and should not be directly used
+/// in user code.
+pub struct TryFnPath<P, Y, F> {
+    path: P,
+    f: F,
+    phantom_y: PhantomData<Y>,
+}
+
+impl<'a, 'b: 'a, X: 'b, Y: 'b, Z: 'b, P: Traverse<'a, 'b, X, Y>, F: Fn(Y) -> Z> Traverse<'a, 'b, X, Z> for FnPath<P, Y, F> {
+    #[inline]
+    fn traverse(&'a self, obj: X) -> Result<Z> {
+        self.path.traverse(obj).map(&self.f)
+    }
+}
+
+impl<'a, 'b: 'a, X: 'b, Y: 'b, Z: 'b, P: Traverse<'a, 'b, X, Y>, F: Fn(Y) -> Result<Z>> Traverse<'a, 'b, X, Z> for TryFnPath<P, Y, F> {
+    #[inline]
+    fn traverse(&'a self, obj: X) -> Result<Z> {
+        self.path.traverse(obj).and_then(&self.f)
+    }
+}
+
+/// The function is not a segment: the path is described by the segments it
+/// passes, like `map` and `and_then` were not applied.
+impl<P: PathInfo, Y, F> PathInfo for FnPath<P, Y, F> {
+    fn push_segments<'a>(&'a self, segments: &mut Vec<Segment<'a>>) {
+        self.path.push_segments(segments);
+    }
+}
+
+/// The function is not a segment: the path is described by the segments it
+/// passes, like `map` and `and_then` were not applied.
+impl<P: PathInfo, Y, F> PathInfo for TryFnPath<P, Y, F> {
+    fn push_segments<'a>(&'a self, segments: &mut Vec<Segment<'a>>) {
+        self.path.push_segments(segments);
+    }
+}
+
+macro_rules! fn_combinators {
+    () => {
+        /// Passes the result of the path through `f`.
+        pub fn map<NY, NF>(self, f: NF) -> FnPath<Self, NY, NF> {
+            FnPath { path: self, f, phantom_y: PhantomData }
+        }
+
+        /// Passes the result of the path through `f`, which may fail.
+        pub fn and_then<NY, NF>(self, f: NF) -> TryFnPath<Self, NY, NF> {
+            TryFnPath { path: self, f, phantom_y: PhantomData }
+        }
+    }
+}
+
+macro_rules! extend_combinators {
+    () => {
+        /// Extends a path by another segment that always succeeds.
+        pub fn from<'a, 'b: 'a, X: 'b, Z: 'b, NX: 'b, NA>(self, attr: NA) -> Path<NX, Z, NA, Self>
+            where NA: Attr<NX, Output=X>,
+                  Self: Traverse<'a, 'b, X, Z> {
+            Path {
+                attr,
+                next: self,
+                phantom_x: PhantomData,
+                phantom_z: PhantomData,
+            }
+        }
+
+        /// Extends a path by another segment that may fail.
+        pub fn try<'a, 'b: 'a, X: 'b, Z: 'b, NX: 'b, NA>(self, attr: NA) -> InsecurePath<NX, Z, NA, Self>
+            where NA: InsecureAttr<NX, Output=X>,
+                  Self: Traverse<'a, 'b, X, Z> {
+            InsecurePath {
+                attr,
+                next: self,
+                phantom_x: PhantomData,
+                phantom_z: PhantomData,
+            }
+        }
+    }
+}
+
+impl<X, Z, A: Attr<X>, R> Path<X, Z, A, R> {
+    fn_combinators!();
+}
+
+impl<X, Z, A: InsecureAttr<X>, R> InsecurePath<X, Z, A, R> {
+    fn_combinators!();
+}
+
+impl<P, Y, F> FnPath<P, Y, F> {
+    fn_combinators!();
+    extend_combinators!();
+}
+
+impl<P, Y, F> TryFnPath<P, Y, F> {
+    fn_combinators!();
+    extend_combinators!();
+}
@@ -0,0 +1,377 @@
+//! Paths through `RefCell`s, `Mutex`es and `RwLock`s.
+//!
+//! Regular paths cannot pass through interior mutability: the guard
+//! returned by `borrow` or `lock` would be dropped in the middle of the
+//! path. Guarded paths have segments that take these guards and keep
+//! them alive until the value at the end of the path is no longer used.
+//!
+//! Guarded paths are built like regular paths, inside out, starting with
+//! `guarded` or `guarded_insecure`. `borrowed`, `locked` and `read_locked`
+//! pass through a `RefCell`, `Mutex` or `RwLock` respectively:
+//!
+//! ```
+//! # use std::cell::RefCell;
+//! # use std::rc::Rc;
+//! # use attr::Attr;
+//! # use attr::guard::{guarded, Guarded};
+//! # struct Service { user: Rc<RefCell<User>> }
+//! # struct User { data: Data }
+//! # struct Data { email: String }
+//! # struct UserAttribute;
+//! # struct DataAttribute;
+//! # struct EmailAttribute;
+//! # impl<'a> Attr<&'a Service> for UserAttribute { type Output = &'a RefCell<User>; fn get(&self, s: &'a Service) -> &'a RefCell<User> { &s.user } fn name(&self) -> &str { "user" } }
+//! # impl<'a> Attr<&'a User> for DataAttribute { type Output = &'a Data; fn get(&self, u: &'a User) -> &'a Data { &u.data } fn name(&self) -> &str { "data" } }
+//! # impl<'a> Attr<&'a Data> for EmailAttribute { type Output = &'a String; fn get(&self, d: &'a Data) -> &'a String { &d.email } fn name(&self) -> &str { "email" } }
+//! # fn main() -> attr::Result<()> {
+//! # let service = Service { user: Rc::new(RefCell::new(User { data: Data { email: "flo@example.com".into() } })) };
+//! // service.user: Rc<RefCell<User>>, user.data.email: String
+//! let path = guarded(EmailAttribute).from(DataAttribute).borrowed().from(UserAttribute);
+//!
+//! let email = path.hold(&service)?;
+//! assert_eq!(&*email, "flo@example.com");
+//! # drop(email);
+//!
+//! let len = path.with(&service, |email| email.len())?;
+//! # assert_eq!(len, 15);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! `hold` returns the value together with all guards taken on the way,
+//! `with` runs a closure while the guards are held. Guards are released
+//! in reverse order of acquisition.
+//!
+//! Attributes used in guarded paths need to return references, for all
+//! lifetimes of their input, which is the case for usual field accessors.
+//!
+//! Note that taking the same `Mutex` twice, or borrowing a `RefCell` that
+//! is already mutably borrowed, fails or deadlocks just like direct access
+//! would.
+
+use std::cell::RefCell;
+use std::fmt;
+use std::marker::PhantomData;
+use std::ops::Deref;
+use std::sync::{Mutex, RwLock};
+
+use super::{Attr, InsecureAttr, Identity, Error, Result};
+
+use self::sealed::{HoldInto, Guards};
+
+mod sealed {
+    use super::Result;
+
+    pub trait Keep {}
+
+    impl<T: ?Sized> Keep for T {}
+
+    /// The guards taken while traversing a guarded path.
+    ///
+    /// Guards are only created by `Guarded::hold`, and never leave this
+    /// module: references handed out by `HoldInto::hold_into` are only valid
+    /// as long as the guards are alive, which only `Held` can ensure.
+    pub struct Guards<'d> {
+        guards: Vec<Box<dyn Keep + 'd>>,
+    }
+
+    impl<'d> Guards<'d> {
+        pub fn new() -> Guards<'d> {
+            Guards { guards: Vec::new() }
+        }
+
+        pub fn keep<G: 'd>(&mut self, guard: G) {
+            self.guards.push(Box::new(guard));
+        }
+
+        pub fn len(&self) -> usize {
+            self.guards.len()
+        }
+    }
+
+    /// Guards are released in reverse order of acquisition, as later guards
+    /// may protect data that is only reachable through earlier ones.
+    impl<'d> Drop for Guards<'d> {
+        fn drop(&mut self) {
+            while self.guards.pop().is_some() {}
+        }
+    }
+
+    /// The traversal behind `Guarded`, which cannot be called or implemented
+    /// outside of this module.
+    ///
+    /// The lifetime is a parameter of the trait rather than of the method, so
+    /// that attributes may lead to types borrowing data for `'d` only.
+    pub trait HoldInto<'d, T: ?Sized + 'd> {
+        /// The type of value the path leads to
+        type Target: ?Sized + 'd;
+
+        /// Traverses the path, adding all guards taken to `guards`.
+        ///
+        /// The returned reference is only valid as long as `guards` is alive,
+        /// not for all of `'d`.
+        fn hold_into(&self, value: &'d T, guards: &mut Guards<'d>) -> Result<&'d Self::Target>;
+    }
+}
+
+/// A value retrieved through a guarded path, together with the guards that protect it.
+///
+/// Dereferences to the value. Neither the `Held` nor the reference it
+/// dereferences to can outlive the data the path started at:
+///
+/// ```compile_fail
+/// use std::cell::RefCell;
+/// use attr::guard::{borrowed, Guarded};
+///
+/// let held = {
+///     let cell = RefCell::new(String::from("flo"));
+///     borrowed().hold(&cell).unwrap()
+/// };
+/// ```
+///
+/// References to the value cannot outlive the `Held`, and with it the guards:
+///
+/// ```compile_fail
+/// use std::cell::RefCell;
+/// use attr::guard::{borrowed, Guarded};
+///
+/// let cell = RefCell::new(String::from("flo"));
+/// let name: &String = {
+///     let held = borrowed().hold(&cell).unwrap();
+///     &*held
+/// };
+/// ```
+pub struct Held<'d, U: ?Sized> {
+    value: *const U,
+    guards: Guards<'d>,
+    phantom_d: PhantomData<&'d U>,
+}
+
+impl<'d, U: ?Sized> Held<'d, U> {
+    /// The number of guards held
+    pub fn guards(&self) -> usize {
+        self.guards.len()
+    }
+}
+
+impl<'d, U: ?Sized> Deref for Held<'d, U> {
+    type Target = U;
+
+    fn deref(&self) -> &U {
+        // SAFETY: The value is borrowed either from the data behind the root
+        // reference, which lives for 'd, or from data protected by one of the
+        // guards. The guards are owned by `self`, cannot be reached from outside
+        // this module and are only released when `self` is dropped, and the
+        // returned reference is bound to the borrow of `self`.
+        unsafe { &*self.value }
+    }
+}
+
+impl<'d, U: ?Sized + fmt::Debug> fmt::Debug for Held<'d, U> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        (**self).fmt(f)
+    }
+}
+
+/// A path that may pass through guards.
+///
+/// Implemented by all segments of this module, and sealed: the traversal
+/// hands out references that are only valid while the guards are held, so
+/// it is only reachable through `hold` and `with`. The type of value the
+/// path leads to is `Target`, `'d` is the lifetime of the data the path
+/// starts at.
+pub trait Guarded<'d, T: ?Sized + 'd>: HoldInto<'d, T> {
+    /// Traverses the path and returns the value along with all guards taken.
+    fn hold(&self, value: &'d T) -> Result<Held<'d, Self::Target>> {
+        let mut guards = Guards::new();
+        let value = self.hold_into(value, &mut guards)? as *const Self::Target;
+        Ok(Held { value, guards, phantom_d: PhantomData })
+    }
+
+    /// Traverses the path and calls `f` with the value, releasing all guards afterwards.
+    fn with<Q, F: FnOnce(&Self::Target) -> Q>(&self, value: &'d T, f: F) -> Result<Q> {
+        let held = self.hold(value)?;
+        Ok(f(&*held))
+    }
+}
+
+/// A guarded path segment retrieving a value through an `Attr`.
+pub struct Step<A, U: ?Sized, R> {
+    attr: A,
+    next: R,
+    phantom_u: PhantomData<fn(&U)>,
+}
+
+/// A guarded path segment retrieving a value through an `InsecureAttr`.
+pub struct TryStep<A, U: ?Sized, R> {
+    attr: A,
+    next: R,
+    phantom_u: PhantomData<fn(&U)>,
+}
+
+/// A guarded path segment passing through a `RefCell`.
+pub struct Borrowed<R> {
+    next: R,
+}
+
+/// A guarded path segment passing through a `Mutex`.
+pub struct Locked<R> {
+    next: R,
+}
+
+/// A guarded path segment passing through a `RwLock`, for reading.
+pub struct ReadLocked<R> {
+    next: R,
+}
+
+/// Starts a guarded path with an attribute that always succeeds.
+pub fn guarded<A, U: ?Sized>(attr: A) -> Step<A, U, Identity> {
+    Step { attr, next: Identity, phantom_u: PhantomData }
+}
+
+/// Starts a guarded path with an attribute that may fail.
+pub fn guarded_insecure<A, U: ?Sized>(attr: A) -> TryStep<A, U, Identity> {
+    TryStep { attr, next: Identity, phantom_u: PhantomData }
+}
+
+/// Starts a guarded path that ends with the contents of a `RefCell`.
+pub fn borrowed() -> Borrowed<Identity> {
+    Borrowed { next: Identity }
+}
+
+/// Starts a guarded path that ends with the contents of a `Mutex`.
+pub fn locked() -> Locked<Identity> {
+    Locked { next: Identity }
+}
+
+/// Starts a guarded path that ends with the contents of a `RwLock`.
+pub fn read_locked() -> ReadLocked<Identity> {
+    ReadLocked { next: Identity }
+}
+
+impl<'d, T: ?Sized + 'd, P: HoldInto<'d, T>> Guarded<'d, T> for P {}
+
+impl<'d, T: ?Sized + 'd> HoldInto<'d, T> for Identity {
+    type Target = T;
+
+    fn hold_into(&self, value: &'d T, _guards: &mut Guards<'d>) -> Result<&'d T> {
+        Ok(value)
+    }
+}
+
+impl<'d, T: ?Sized + 'd, U: ?Sized + 'd, A, R: HoldInto<'d, U>> HoldInto<'d, T> for Step<A, U, R>
+    where A: for<'x> Attr<&'x T, Output=&'x U>
+{
+    type Target = R::Target;
+
+    fn hold_into(&self, value: &'d T, guards: &mut Guards<'d>) -> Result<&'d R::Target> {
+        self.next.hold_into(self.attr.get(value), guards)
+    }
+}
+
+impl<'d, T: ?Sized + 'd, U: ?Sized + 'd, A, R: HoldInto<'d, U>> HoldInto<'d, T> for TryStep<A, U, R>
+    where A: for<'x> InsecureAttr<&'x T, Output=&'x U>
+{
+    type Target = R::Target;
+
+    fn hold_into(&self, value: &'d T, guards: &mut Guards<'d>) -> Result<&'d R::Target> {
+        self.next.hold_into(self.attr.get(value)?, guards)
+    }
+}
+
+impl<'d, U: ?Sized + 'd, R: HoldInto<'d, U>> HoldInto<'d, RefCell<U>> for Borrowed<R> {
+    type Target = R::Target;
+
+    fn hold_into(&self, value: &'d RefCell<U>, guards: &mut Guards<'d>) -> Result<&'d R::Target> {
+        let guard = value.try_borrow().map_err(|_| Error::invalid("value is already mutably borrowed"))?;
+        // SAFETY: The value lives in the `RefCell`, which is borrowed for 'd,
+        // not in the guard. The guard is moved into `guards`, which `hold`
+        // keeps in the returned `Held` and which is never emptied or replaced
+        // before it is dropped, so the borrow flag stays set as long as the
+        // extended reference is reachable.
+        let inner = unsafe { &*(&*guard as *const U) };
+        guards.keep(guard);
+        self.next.hold_into(inner, guards)
+    }
+}
+
+impl<'d, U: ?Sized + 'd, R: HoldInto<'d, U>> HoldInto<'d, Mutex<U>> for Locked<R> {
+    type Target = R::Target;
+
+    fn hold_into(&self, value: &'d Mutex<U>, guards: &mut Guards<'d>) -> Result<&'d R::Target> {
+        let guard = value.lock().map_err(|_| Error::invalid("mutex is poisoned"))?;
+        // SAFETY: The value lives in the `Mutex`, which is borrowed for 'd, not
+        // in the guard. The guard is moved into `guards`, which `hold` keeps in
+        // the returned `Held` and which is never emptied or replaced before it
+        // is dropped, so the lock stays held as long as the extended reference
+        // is reachable.
+        let inner = unsafe { &*(&*guard as *const U) };
+        guards.keep(guard);
+        self.next.hold_into(inner, guards)
+    }
+}
+
+impl<'d, U: ?Sized + 'd, R: HoldInto<'d, U>> HoldInto<'d, RwLock<U>> for ReadLocked<R> {
+    type Target = R::Target;
+
+    fn hold_into(&self, value: &'d RwLock<U>, guards: &mut Guards<'d>) -> Result<&'d R::Target> {
+        let guard = value.read().map_err(|_| Error::invalid("lock is poisoned"))?;
+        // SAFETY: The value lives in the `RwLock`, which is borrowed for 'd, not
+        // in the guard. The guard is moved into `guards`, which `hold` keeps in
+        // the returned `Held` and which is never emptied or replaced before it
+        // is dropped, so the read lock stays held as long as the extended
+        // reference is reachable.
+        let inner = unsafe { &*(&*guard as *const U) };
+        guards.keep(guard);
+        self.next.hold_into(inner, guards)
+    }
+}
+
+macro_rules! guard_combinators {
+    () => {
+        /// Extends the path by another segment that always succeeds.
+        pub fn from<NA, NU: ?Sized>(self, attr: NA) -> Step<NA, NU, Self> {
+            Step { attr, next: self, phantom_u: PhantomData }
+        }
+
+        /// Extends the path by another segment that may fail.
+        pub fn try<NA, NU: ?Sized>(self, attr: NA) -> TryStep<NA, NU, Self> {
+            TryStep { attr, next: self, phantom_u: PhantomData }
+        }
+
+        /// Extends the path by borrowing a `RefCell`.
+        pub fn borrowed(self) -> Borrowed<Self> {
+            Borrowed { next: self }
+        }
+
+        /// Extends the path by locking a `Mutex`.
+        pub fn locked(self) -> Locked<Self> {
+            Locked { next: self }
+        }
+
+        /// Extends the path by locking a `RwLock` for reading.
+        pub fn read_locked(self) -> ReadLocked<Self> {
+            ReadLocked { next: self }
+        }
+    }
+}
+
+impl<A, U: ?Sized, R> Step<A, U, R> {
+    guard_combinators!();
+}
+
+impl<A, U: ?Sized, R> TryStep<A, U, R> {
+    guard_combinators!();
+}
+
+impl<R> Borrowed<R> {
+    guard_combinators!();
+}
+
+impl<R> Locked<R> {
+    guard_combinators!();
+}
+
+impl<R> ReadLocked<R> {
+    guard_combinators!();
+}
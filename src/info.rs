@@ -0,0 +1,240 @@
+use std::fmt;
+
+#[cfg(feature = "serde")]
+use serde::{Serialize, Deserialize};
+
+use super::{Attr, InsecureAttr, Identity, Path, InsecurePath, MapPath, JoinPath, FnPath, TryFnPath, FallbackPath, DefaultPath, OptionalPath, RecursivePath};
+
+/// The kind of a path segment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum SegmentKind {
+    /// A retrieval that always succeeds, through an `Attr`
+    Attr,
+    /// A retrieval that may fail, through an `InsecureAttr`
+    Insecure,
+    /// An iteration, through an `IterableAttr`
+    Iterable,
+}
+
+/// Description of a single segment of a path.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Segment<'a> {
+    /// The name of the attribute
+    pub name: &'a str,
+    /// How the attribute is accessed
+    pub kind: SegmentKind,
+}
+
+/// Information about the segments of a path, for logging and diagnostics.
+///
+/// Paths implement `Display` based on this information, rendering
+/// a path like `foo.vector[*].name`.
+pub trait PathInfo {
+    /// Appends the segments of this path to `segments`, in order of traversal.
+    fn push_segments<'a>(&'a self, segments: &mut Vec<Segment<'a>>);
+
+    /// The segments of this path, in order of traversal.
+    fn segments(&self) -> Vec<Segment<'_>> {
+        let mut segments = Vec::new();
+        self.push_segments(&mut segments);
+        segments
+    }
+
+    /// The number of segments
+    fn len(&self) -> usize {
+        self.segments().len()
+    }
+
+    /// Whether the path has no segments
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Whether the path contains segments that may fail
+    fn is_fallible(&self) -> bool {
+        self.segments().iter().any(|s| s.kind == SegmentKind::Insecure)
+    }
+
+    /// Whether the path contains segments that iterate
+    fn is_iterating(&self) -> bool {
+        self.segments().iter().any(|s| s.kind == SegmentKind::Iterable)
+    }
+
+    /// Renders the path with the first iteration replaced by `index`,
+    /// like `foo.vector[2].name`, to point at a single item.
+    fn breadcrumb(&self, index: Option<usize>) -> String {
+        let mut crumb = String::new();
+        write_segments(&mut crumb, &self.segments(), index).expect("writing to a String does not fail");
+        crumb
+    }
+
+    /// An owned description of this path, which can be stored and
+    /// later resolved through a `Registry`.
+    fn descriptor(&self) -> PathDescriptor {
+        PathDescriptor {
+            segments: self.segments().iter().map(|s| SegmentDescriptor { name: s.name.to_owned(), kind: s.kind }).collect()
+        }
+    }
+}
+
+/// Owned description of a single segment of a path.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SegmentDescriptor {
+    /// The name of the attribute
+    pub name: String,
+    /// How the attribute is accessed
+    pub kind: SegmentKind,
+}
+
+/// Owned description of a path, as a list of segment names and kinds.
+///
+/// With the `serde` feature enabled, descriptors can be serialized, for
+/// example to store them in a database. They are turned back into paths
+/// through `Registry::resolve`.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PathDescriptor {
+    /// The segments, in order of traversal
+    pub segments: Vec<SegmentDescriptor>,
+}
+
+impl PathDescriptor {
+    /// Creates an empty descriptor.
+    pub fn new() -> PathDescriptor {
+        PathDescriptor::default()
+    }
+
+    /// Appends a segment to the descriptor.
+    pub fn segment<N: Into<String>>(mut self, name: N, kind: SegmentKind) -> PathDescriptor {
+        self.segments.push(SegmentDescriptor { name: name.into(), kind });
+        self
+    }
+}
+
+impl PathInfo for PathDescriptor {
+    fn push_segments<'a>(&'a self, segments: &mut Vec<Segment<'a>>) {
+        segments.extend(self.segments.iter().map(|s| Segment { name: &s.name, kind: s.kind }));
+    }
+}
+
+impl PathInfo for Identity {
+    fn push_segments<'a>(&'a self, _segments: &mut Vec<Segment<'a>>) {}
+}
+
+impl<X, Z, A: Attr<X>, R: PathInfo> PathInfo for Path<X, Z, A, R> {
+    fn push_segments<'a>(&'a self, segments: &mut Vec<Segment<'a>>) {
+        segments.push(Segment { name: self.attr.name(), kind: SegmentKind::Attr });
+        self.next.push_segments(segments);
+    }
+}
+
+impl<X, Z, A: InsecureAttr<X>, R: PathInfo> PathInfo for InsecurePath<X, Z, A, R> {
+    fn push_segments<'a>(&'a self, segments: &mut Vec<Segment<'a>>) {
+        segments.push(Segment { name: self.attr.name(), kind: SegmentKind::Insecure });
+        self.next.push_segments(segments);
+    }
+}
+
+impl<X, A: Attr<X>, R: PathInfo> PathInfo for MapPath<X, A, R> {
+    fn push_segments<'a>(&'a self, segments: &mut Vec<Segment<'a>>) {
+        segments.push(Segment { name: self.attr.name(), kind: SegmentKind::Iterable });
+        self.next.push_segments(segments);
+    }
+}
+
+/// Joins `segments` with dots, marking iterations with `[*]`, or the
+/// first one with `[index]` if an index is given.
+fn write_segments<W: fmt::Write>(out: &mut W, segments: &[Segment], index: Option<usize>) -> fmt::Result {
+    let mut index = index;
+    for (i, segment) in segments.iter().enumerate() {
+        if i > 0 {
+            out.write_str(".")?;
+        }
+        out.write_str(segment.name)?;
+        if segment.kind == SegmentKind::Iterable {
+            match index.take() {
+                Some(index) => write!(out, "[{}]", index)?,
+                None => out.write_str("[*]")?,
+            }
+        }
+    }
+    Ok(())
+}
+
+fn fmt_segments(segments: &[Segment], f: &mut fmt::Formatter) -> fmt::Result {
+    write_segments(f, segments, None)
+}
+
+impl fmt::Display for PathDescriptor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt_segments(&self.segments(), f)
+    }
+}
+
+impl fmt::Display for Identity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt_segments(&self.segments(), f)
+    }
+}
+
+impl<X, Z, A: Attr<X>, R: PathInfo> fmt::Display for Path<X, Z, A, R> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt_segments(&self.segments(), f)
+    }
+}
+
+impl<X, Z, A: InsecureAttr<X>, R: PathInfo> fmt::Display for InsecurePath<X, Z, A, R> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt_segments(&self.segments(), f)
+    }
+}
+
+impl<X, A: Attr<X>, R: PathInfo> fmt::Display for MapPath<X, A, R> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt_segments(&self.segments(), f)
+    }
+}
+
+impl<P: PathInfo, Y, Q: PathInfo> fmt::Display for JoinPath<P, Y, Q> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt_segments(&self.segments(), f)
+    }
+}
+
+impl<P: PathInfo, Y, F> fmt::Display for FnPath<P, Y, F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt_segments(&self.segments(), f)
+    }
+}
+
+impl<P: PathInfo, Y, F> fmt::Display for TryFnPath<P, Y, F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt_segments(&self.segments(), f)
+    }
+}
+
+impl<P: PathInfo, Q> fmt::Display for FallbackPath<P, Q> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt_segments(&self.segments(), f)
+    }
+}
+
+impl<P: PathInfo, Y> fmt::Display for DefaultPath<P, Y> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt_segments(&self.segments(), f)
+    }
+}
+
+impl<P: PathInfo> fmt::Display for OptionalPath<P> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt_segments(&self.segments(), f)
+    }
+}
+
+impl<D> fmt::Display for RecursivePath<D> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt_segments(&self.segments(), f)
+    }
+}
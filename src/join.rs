@@ -0,0 +1,67 @@
+use std::marker::PhantomData;
+
+use super::{Attr, InsecureAttr, Path, InsecurePath, MapPath, Traverse, PathInfo, Segment, Result};
+
+/// A path that traverses a first path and then a second path
+/// on its result.
+///
+/// Joining paths of zero-sized attributes is zero-sized as well.
+///
+/// Paths are usually inferred and should not be directly used
+/// in user code.
+pub struct JoinPath<First, Y, Second> {
+    first: First,
+    second: Second,
+    phantom_y: PhantomData<Y>,
+}
+
+impl<'a, 'b: 'a, X: 'b, Y: 'b, Z: 'b, P: Traverse<'a, 'b, X, Y>, Q: Traverse<'a, 'b, Y, Z>> Traverse<'a, 'b, X, Z> for JoinPath<P, Y, Q> {
+    #[inline]
+    fn traverse(&'a self, obj: X) -> Result<Z> {
+        let val = self.first.traverse(obj)?;
+        self.second.traverse(val)
+    }
+}
+
+impl<P: PathInfo, Y, Q: PathInfo> PathInfo for JoinPath<P, Y, Q> {
+    fn push_segments<'a>(&'a self, segments: &mut Vec<Segment<'a>>) {
+        self.first.push_segments(segments);
+        self.second.push_segments(segments);
+    }
+}
+
+macro_rules! join {
+    () => {
+        /// Concatenates two paths: `other` is traversed on the result of this path.
+        ///
+        /// Note that, unlike segments added through `from`, the joined path
+        /// is traversed last.
+        pub fn join<NY, NQ>(self, other: NQ) -> JoinPath<Self, NY, NQ> {
+            JoinPath { first: self, second: other, phantom_y: PhantomData }
+        }
+    }
+}
+
+impl<X, Z, A: Attr<X>, R> Path<X, Z, A, R> {
+    join!();
+}
+
+impl<X, Z, A: InsecureAttr<X>, R> InsecurePath<X, Z, A, R> {
+    join!();
+}
+
+impl<P, Y, Q> JoinPath<P, Y, Q> {
+    join!();
+}
+
+impl<X, A: Attr<X>, R> MapPath<X, A, R> {
+    /// Concatenates two paths: `other` is traversed on every item
+    /// this path yields.
+    pub fn join<Y, Q>(self, other: Q) -> MapPath<X, A, JoinPath<R, Y, Q>> {
+        MapPath {
+            attr: self.attr,
+            next: JoinPath { first: self.next, second: other, phantom_y: PhantomData },
+            phantom_x: PhantomData,
+        }
+    }
+}
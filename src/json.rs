@@ -0,0 +1,397 @@
+//! Access to `serde_json::Value`s, available with the `json` feature.
+//!
+//! `serde_json::Value` implements `DynValue`, so the `Key`, `Index` and
+//! `Each` attributes of `attr::value` apply to it. `Pointer` implements
+//! JSON Pointers (RFC 6901) on top of `Key` and can be used as a path. `Query` selects any number of values, using
+//! dotted paths, JSON Pointers or a subset of JSONPath.
+//!
+//! ```
+//! # #[macro_use] extern crate serde_json;
+//! # extern crate attr;
+//! # use attr::Traverse;
+//! # use attr::json::Pointer;
+//! # fn main() -> attr::Result<()> {
+//! # let value = json!({ "user": { "data": { "email": "flo@example.com" } } });
+//! let pointer = Pointer::parse("/user/data/email")?;
+//! let email = pointer.traverse(&value)?;
+//! # assert_eq!(email, "flo@example.com");
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Absent members and out of bounds indices fail with `ErrorKind::Missing`,
+//! values of the wrong shape with `ErrorKind::Invalid`.
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Serialize, Serializer, Deserialize, Deserializer};
+use serde::de;
+use serde_json::Value;
+
+use super::value::Kind;
+use super::{InsecureAttr, Traverse, DynValue, PathInfo, Segment, SegmentKind, Error, Result, parse_index};
+
+pub use value::{Key, Index, Each};
+
+impl DynValue for Value {
+    fn kind(&self) -> Kind {
+        match *self {
+            Value::Null => Kind::Null,
+            Value::Bool(_) => Kind::Bool,
+            Value::Number(_) => Kind::Number,
+            Value::String(_) => Kind::String,
+            Value::Array(_) => Kind::List,
+            Value::Object(_) => Kind::Map,
+        }
+    }
+
+    fn get_key(&self, key: &str) -> Option<&Value> {
+        self.as_object().and_then(|m| m.get(key))
+    }
+
+    fn get_key_mut(&mut self, key: &str) -> Option<&mut Value> {
+        self.as_object_mut().and_then(|m| m.get_mut(key))
+    }
+
+    fn get_index(&self, index: usize) -> Option<&Value> {
+        self.as_array().and_then(|v| v.get(index))
+    }
+
+    fn get_index_mut(&mut self, index: usize) -> Option<&mut Value> {
+        self.as_array_mut().and_then(|v| v.get_mut(index))
+    }
+
+    fn values<'a>(&'a self) -> Box<dyn Iterator<Item=&'a Value> + 'a> {
+        match *self {
+            Value::Array(ref v) => Box::new(v.iter()),
+            Value::Object(ref m) => Box::new(m.values()),
+            _ => Box::new(std::iter::empty()),
+        }
+    }
+}
+
+/// A JSON Pointer, as specified in RFC 6901.
+///
+/// The empty pointer refers to the whole document.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct Pointer {
+    tokens: Vec<String>,
+}
+
+impl Pointer {
+    /// The pointer to the whole document.
+    pub fn root() -> Pointer {
+        Pointer::default()
+    }
+
+    /// Parses a pointer like `/user/tags/0`.
+    ///
+    /// `~1` and `~0` are unescaped to `/` and `~`.
+    pub fn parse(pointer: &str) -> Result<Pointer> {
+        if pointer.is_empty() {
+            return Ok(Pointer::root());
+        }
+
+        if !pointer.starts_with('/') {
+            return Err(Error::invalid(format!("pointer {} does not start with /", pointer)));
+        }
+
+        let tokens = pointer[1..].split('/').map(|token| {
+            let mut unescaped = String::with_capacity(token.len());
+            let mut chars = token.chars();
+            while let Some(c) = chars.next() {
+                match c {
+                    '~' => match chars.next() {
+                        Some('0') => unescaped.push('~'),
+                        Some('1') => unescaped.push('/'),
+                        _ => return Err(Error::invalid(format!("pointer {} contains an invalid escape", pointer))),
+                    },
+                    c => unescaped.push(c),
+                }
+            }
+            Ok(unescaped)
+        }).collect::<Result<Vec<String>>>()?;
+
+        Ok(Pointer { tokens })
+    }
+
+    /// The pointer to a member or element of the value this pointer refers to.
+    pub fn child<T: Into<String>>(&self, token: T) -> Pointer {
+        let mut tokens = self.tokens.clone();
+        tokens.push(token.into());
+        Pointer { tokens }
+    }
+
+    /// The pointer to the containing value and the last token, unless this is the root pointer.
+    pub fn split_last(&self) -> Option<(Pointer, &str)> {
+        self.tokens.split_last().map(|(last, parent)| (Pointer { tokens: parent.to_vec() }, last.as_str()))
+    }
+
+    /// The unescaped reference tokens.
+    pub fn tokens(&self) -> &[String] {
+        &self.tokens
+    }
+
+    /// Whether this is the pointer to the whole document
+    pub fn is_root(&self) -> bool {
+        self.tokens.is_empty()
+    }
+
+    /// Whether `other` points into the value this pointer refers to, excluding the value itself.
+    pub fn is_proper_prefix_of(&self, other: &Pointer) -> bool {
+        self.tokens.len() < other.tokens.len() && other.tokens.starts_with(&self.tokens)
+    }
+
+    /// Retrieves the value this pointer refers to.
+    ///
+    /// Pointers apply to any `DynValue`, not only to JSON documents.
+    pub fn get<'a, V: DynValue>(&self, value: &'a V) -> Result<&'a V> {
+        let mut current = value;
+        for token in &self.tokens {
+            current = Key::new(token).get(current)?;
+        }
+        Ok(current)
+    }
+
+    /// Retrieves the value this pointer refers to, for modification.
+    pub fn get_mut<'a, V: DynValue>(&self, value: &'a mut V) -> Result<&'a mut V> {
+        let mut current = value;
+        for token in &self.tokens {
+            current = Key::new(token).get(current)?;
+        }
+        Ok(current)
+    }
+}
+
+impl<'a, 'b: 'a, V: DynValue> Traverse<'a, 'b, &'b V, &'b V> for Pointer {
+    #[inline]
+    fn traverse(&'a self, obj: &'b V) -> Result<&'b V> {
+        self.get(obj)
+    }
+}
+
+impl<'a, 'b: 'a, V: DynValue> Traverse<'a, 'b, &'b mut V, &'b mut V> for Pointer {
+    #[inline]
+    fn traverse(&'a self, obj: &'b mut V) -> Result<&'b mut V> {
+        self.get_mut(obj)
+    }
+}
+
+impl PathInfo for Pointer {
+    fn push_segments<'a>(&'a self, segments: &mut Vec<Segment<'a>>) {
+        segments.extend(self.tokens.iter().map(|t| Segment { name: t, kind: SegmentKind::Insecure }));
+    }
+}
+
+impl FromStr for Pointer {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Pointer> {
+        Pointer::parse(s)
+    }
+}
+
+/// Renders the pointer in its escaped form, like `/a~1b/0`.
+impl fmt::Display for Pointer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for token in &self.tokens {
+            write!(f, "/{}", token.replace('~', "~0").replace('/', "~1"))?;
+        }
+        Ok(())
+    }
+}
+
+impl Serialize for Pointer {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Pointer {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Pointer, D::Error> {
+        let pointer = String::deserialize(deserializer)?;
+        Pointer::parse(&pointer).map_err(de::Error::custom)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Step {
+    Key(String),
+    Wildcard,
+}
+
+/// A query selecting any number of values, in one of three notations:
+///
+/// * dotted paths like `user.orders[*].id`, as rendered by `PathInfo::breadcrumb`
+/// * JSON Pointers like `/user/orders/0/id`
+/// * a subset of JSONPath: `$`, `.name`, `['name']`, `[0]`, `.*` and `[*]`
+///
+/// Wildcards select all elements of an array or all members of an object.
+/// Failures are reported with the location reached so far, like
+/// `user.orders[2].id: id is not present`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Query {
+    steps: Vec<Step>,
+}
+
+impl Query {
+    /// Parses a query, picking the notation by its first character.
+    pub fn parse(query: &str) -> Result<Query> {
+        if query.is_empty() || query.starts_with('/') {
+            let pointer = Pointer::parse(query)?;
+            return Ok(Query { steps: pointer.tokens.into_iter().map(Step::Key).collect() });
+        }
+
+        if query.starts_with('$') {
+            parse_steps(query, 1, false)
+        } else {
+            parse_steps(query, 0, true)
+        }
+    }
+
+    /// Whether the query selects at most one value
+    pub fn is_single(&self) -> bool {
+        !self.steps.contains(&Step::Wildcard)
+    }
+
+    /// Retrieves all values selected by the query, in document order.
+    pub fn select<'a>(&self, value: &'a Value) -> Result<Vec<&'a Value>> {
+        let mut selected = Vec::new();
+        select_into(&self.steps, value, &mut String::new(), &mut selected)?;
+        Ok(selected)
+    }
+}
+
+fn parse_steps(query: &str, start: usize, dotted: bool) -> Result<Query> {
+    let unexpected = |at: usize| Error::invalid(format!("unexpected character at position {} in {}", at, query));
+    let bytes = query.as_bytes();
+    let mut steps = Vec::new();
+    let mut at = start;
+
+    while at < bytes.len() {
+        let name_at = match bytes[at] {
+            b'.' => at + 1,
+            b'[' => {
+                let rest = &query[at + 1..];
+                let (step, len) = if rest.starts_with('\'') || rest.starts_with('"') {
+                    let close = rest[1..].find(&rest[..1]).ok_or_else(|| unexpected(query.len()))?;
+                    (Step::Key(rest[1..close + 1].to_owned()), close + 2)
+                } else {
+                    let close = rest.find(']').ok_or_else(|| unexpected(query.len()))?;
+                    match &rest[..close] {
+                        "*" => (Step::Wildcard, close),
+                        index => (Step::Key(parse_index(index)?.to_string()), close),
+                    }
+                };
+                if !rest[len..].starts_with(']') {
+                    return Err(unexpected(at + 1 + len));
+                }
+                steps.push(step);
+                at += len + 2;
+                continue;
+            },
+            _ if dotted && at == start => at,
+            _ => return Err(unexpected(at)),
+        };
+
+        let end = query[name_at..].find(['.', '[']).map_or(query.len(), |i| name_at + i);
+        match &query[name_at..end] {
+            "" => return Err(unexpected(name_at)),
+            "*" => steps.push(Step::Wildcard),
+            name => steps.push(Step::Key(name.to_owned())),
+        }
+        at = end;
+    }
+
+    Ok(Query { steps })
+}
+
+fn select_into<'a>(steps: &[Step], value: &'a Value, location: &mut String, selected: &mut Vec<&'a Value>) -> Result<()> {
+    let (step, rest) = match steps.split_first() {
+        Some(split) => split,
+        None => {
+            selected.push(value);
+            return Ok(());
+        }
+    };
+
+    let len = location.len();
+    match *step {
+        Step::Key(ref name) => {
+            push_location(location, value, name);
+            let next = Key::new(name).get(value)
+                .map_err(|e| Error::new(e.kind(), format!("{}: {}", location, e.message())))?;
+            select_into(rest, next, location, selected)?;
+        },
+        Step::Wildcard => match *value {
+            Value::Array(ref v) => for (i, item) in v.iter().enumerate() {
+                push_location(location, value, &i.to_string());
+                select_into(rest, item, location, selected)?;
+                location.truncate(len);
+            },
+            Value::Object(ref m) => for (name, member) in m {
+                push_location(location, value, name);
+                select_into(rest, member, location, selected)?;
+                location.truncate(len);
+            },
+            _ => {
+                location.push_str(if location.is_empty() { "*" } else { ".*" });
+                return Err(Error::invalid(format!("{}: cannot iterate over a value that is neither object nor array", location)));
+            },
+        },
+    }
+    location.truncate(len);
+    Ok(())
+}
+
+fn push_location(location: &mut String, value: &Value, name: &str) {
+    if value.is_array() {
+        location.push('[');
+        location.push_str(name);
+        location.push(']');
+    } else {
+        if !location.is_empty() {
+            location.push('.');
+        }
+        location.push_str(name);
+    }
+}
+
+impl<'a, 'b: 'a> Traverse<'a, 'b, &'b Value, Vec<&'b Value>> for Query {
+    #[inline]
+    fn traverse(&'a self, obj: &'b Value) -> Result<Vec<&'b Value>> {
+        self.select(obj)
+    }
+}
+
+impl FromStr for Query {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Query> {
+        Query::parse(s)
+    }
+}
+
+/// Renders the query in dotted notation, like `user.orders[*].id`.
+impl fmt::Display for Query {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, step) in self.steps.iter().enumerate() {
+            match *step {
+                Step::Wildcard => f.write_str("[*]")?,
+                Step::Key(ref name) if name.bytes().all(|b| b.is_ascii_digit()) && !name.is_empty() => write!(f, "[{}]", name)?,
+                Step::Key(ref name) if name.is_empty() || name.contains(['.', '[', ']', '*']) => {
+                    let quote = if name.contains('\'') { '"' } else { '\'' };
+                    write!(f, "[{}{}{}]", quote, name, quote)?
+                },
+                Step::Key(ref name) => {
+                    if i > 0 {
+                        f.write_str(".")?;
+                    }
+                    f.write_str(name)?;
+                },
+            }
+        }
+        Ok(())
+    }
+}
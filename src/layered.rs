@@ -0,0 +1,167 @@
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+use super::{Traverse, Error, Result};
+use fallback::fall_back;
+#[cfg(feature = "serde")]
+use super::value::{to_value, Value};
+
+/// A value found in one of the layers of a `Layered`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Hit<'l, Y> {
+    value: Y,
+    layer: &'l str,
+    index: usize,
+}
+
+impl<'l, Y> Hit<'l, Y> {
+    /// The value found
+    pub fn value(&self) -> &Y {
+        &self.value
+    }
+
+    /// The name of the layer that provided the value
+    pub fn layer(&self) -> &'l str {
+        self.layer
+    }
+
+    /// The position of the layer that provided the value
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Unwraps the value.
+    pub fn into_value(self) -> Y {
+        self.value
+    }
+}
+
+/// An ordered list of named values, for example configuration from
+/// command line options, the environment, a file and built-in defaults.
+///
+/// All layers have the same type. Sources of different types are added
+/// to a `Layered<Value>` with `source`, which converts them into `Value`s,
+/// and are then queried with the paths of the `value` module.
+///
+/// Paths are evaluated against each layer in turn and the first hit wins.
+/// Layers fall through to the next one in the same way as the paths of a
+/// `FallbackPath`: layers where the value is absent are skipped, any other
+/// failure is returned, naming the layer.
+///
+/// Layers are tried in the order they were added, so the most specific
+/// one is added first:
+///
+/// ```
+/// # use std::collections::BTreeMap;
+/// # use attr::{retrieve_insecure, Layered, Value};
+/// # use attr::value::Key;
+/// # fn user(email: Option<&str>) -> Value {
+/// #     let mut data = BTreeMap::new();
+/// #     if let Some(email) = email { data.insert("email".to_string(), Value::from(email)); }
+/// #     let mut user = BTreeMap::new();
+/// #     user.insert("data".to_string(), Value::Map(data));
+/// #     Value::Map(user)
+/// # }
+/// # fn main() -> attr::Result<()> {
+/// # let (cli, env, defaults) = (user(None), user(Some("flo@example.com")), user(Some("root@localhost")));
+/// let config = Layered::new()
+///     .layer("cli", cli)
+///     .layer("env", env)
+///     .layer("defaults", defaults);
+///
+/// let email = config.get(&retrieve_insecure(Key::new("email")).try(Key::new("data")))?;
+/// println!("{:?} from {}", email.value(), email.layer());
+/// # assert_eq!(email.layer(), "env");
+/// # Ok(())
+/// # }
+/// ```
+pub struct Layered<T> {
+    layers: Vec<(String, T)>,
+}
+
+impl<T> Default for Layered<T> {
+    fn default() -> Layered<T> {
+        Layered { layers: Vec::new() }
+    }
+}
+
+impl<T> Layered<T> {
+    /// Creates an empty list of layers.
+    pub fn new() -> Layered<T> {
+        Layered::default()
+    }
+
+    /// Adds a layer, which is tried after all layers added before.
+    pub fn layer<N: Into<String>>(mut self, name: N, value: T) -> Layered<T> {
+        self.push(name, value);
+        self
+    }
+
+    /// Adds a layer, which is tried after all layers added before.
+    pub fn push<N: Into<String>>(&mut self, name: N, value: T) {
+        self.layers.push((name.into(), value));
+    }
+
+    /// The names of the layers, in order
+    pub fn names(&self) -> Vec<&str> {
+        self.layers.iter().map(|(name, _)| name.as_str()).collect()
+    }
+
+    /// The layer with the given name
+    pub fn find(&self, name: &str) -> Option<&T> {
+        self.layers.iter().find(|(n, _)| n == name).map(|(_, value)| value)
+    }
+
+    /// Retrieves the value at `path` from the first layer providing it.
+    pub fn get<'a, 'b: 'a, P, Y: 'b>(&'b self, path: &'a P) -> Result<Hit<'b, Y>>
+        where P: Traverse<'a, 'b, &'b T, Y>,
+              T: 'b
+    {
+        // Chained like `first.or(second).or(third)`, one layer at a time.
+        let mut found = Err(Error::missing("no layers"));
+        for (index, (name, value)) in self.layers.iter().enumerate() {
+            found = fall_back(found, || match path.traverse(value) {
+                Ok(value) => Ok(Hit { value, layer: name, index }),
+                Err(e) => Err(if e.is_missing() { e } else { e.context(name) }),
+            });
+        }
+
+        found.map_err(|e| if e.is_missing() { Error::missing(format!("not present in any of {}", self.names().join(", "))) } else { e })
+    }
+
+    /// Retrieves the value at `path` from every layer, to trace where a value comes from.
+    pub fn trace<'a, 'b: 'a, P, Y: 'b>(&'b self, path: &'a P) -> Vec<(&'b str, Result<Y>)>
+        where P: Traverse<'a, 'b, &'b T, Y>,
+              T: 'b
+    {
+        self.layers.iter().map(|(name, value)| (name.as_str(), path.traverse(value))).collect()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Layered<Value> {
+    /// Adds a layer holding `source` converted into a `Value`, which is
+    /// tried after all layers added before.
+    ///
+    /// Fails if `source` cannot be converted.
+    ///
+    /// ```
+    /// # extern crate serde_json;
+    /// # use std::collections::BTreeMap;
+    /// # use attr::{retrieve_insecure, Layered};
+    /// # use attr::value::Key;
+    /// # fn main() -> attr::Result<()> {
+    /// # let file: serde_json::Value = serde_json::from_str(r#"{"server": {"port": 8080}}"#).unwrap();
+    /// let config = Layered::new()
+    ///     .source("env", &std::env::vars().collect::<BTreeMap<_, _>>())?
+    ///     .source("file", &file)?;
+    ///
+    /// let port = config.get(&retrieve_insecure(Key::new("port")).try(Key::new("server")))?;
+    /// # assert_eq!(port.layer(), "file");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn source<N: Into<String>, S: Serialize + ?Sized>(self, name: N, source: &S) -> Result<Layered<Value>> {
+        Ok(self.layer(name, to_value(source)?))
+    }
+}
@@ -4,9 +4,79 @@
 
 use std::marker::PhantomData;
 
+#[cfg(feature = "serde")]
+extern crate serde;
+#[cfg(feature = "json")]
+extern crate serde_json;
+#[cfg(feature = "async")]
+extern crate futures_core;
+#[cfg(feature = "rayon")]
+extern crate rayon;
+#[cfg(feature = "toml")]
+extern crate toml as toml_crate;
+
+mod aggregate;
+mod dynamic;
+mod error;
+mod function;
+mod info;
+mod recursive;
+mod fallback;
+mod join;
+mod layered;
+mod observe;
+mod optional;
+#[cfg(feature = "rayon")]
+mod parallel;
+#[cfg(feature = "serde")]
+mod serialize;
+mod zip;
+
+#[cfg(feature = "async")]
+pub mod asynchronous;
+pub mod cmp;
+pub mod diff;
+pub mod env;
+pub mod guard;
+#[cfg(feature = "json")]
+pub mod json;
+#[cfg(feature = "json")]
+pub mod patch;
+pub mod template;
+#[cfg(feature = "toml")]
+pub mod toml;
+pub mod validate;
+pub mod value;
+
+pub use aggregate::Aggregate;
+#[cfg(feature = "async")]
+pub use asynchronous::{AsyncAttr, AsyncInsecureAttr, AsyncTraverse, retrieve_async, retrieve_async_insecure};
+pub use dynamic::{Registry, DynPath, AnyValue, IntoAny};
+pub use error::{Error, ErrorKind};
+pub use function::{attr_fn, insecure_attr_fn, FnAttr, InsecureFnAttr, FnPath, TryFnPath};
+pub use fallback::{FallbackPath, DefaultPath};
+pub use join::JoinPath;
+pub use layered::{Layered, Hit};
+pub use info::{PathInfo, Segment, SegmentKind, PathDescriptor, SegmentDescriptor};
+pub use observe::{Observable, Notification, ObserverId};
+pub use optional::OptionalPath;
+#[cfg(feature = "rayon")]
+pub use parallel::{ParTraverse, ParMap};
+pub use zip::{zip, ZipPath};
+pub use value::{DynValue, Value};
+pub use recursive::{recursive, recursive_insecure, RecursivePath, Descend, Children, Link, Order, Walk};
+
 /// In case of failed traversals, this Result type is
 /// returned.
-pub type Result<X> = std::result::Result<X, String>;
+pub type Result<X> = std::result::Result<X, Error>;
+
+/// Parses an array index, which must not have leading zeros.
+fn parse_index(token: &str) -> Result<usize> {
+    if token.is_empty() || (token.len() > 1 && token.starts_with('0')) || !token.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(Error::invalid(format!("{} is not an array index", token)));
+    }
+    token.parse().map_err(|_| Error::invalid(format!("{} is not an array index", token)))
+}
 
 /// Direct access to an attribute of a type.
 ///
@@ -63,7 +133,7 @@ pub trait IterableAttr<'a, Type: ?Sized> : Attr<Type> {
     type Item: 'a;
 
     /// Retrieval of an Iterator
-    fn iter(&self, i: Type) -> Box<Iterator<Item=Self::Item> + 'a>;
+    fn iter(&self, i: Type) -> Box<dyn Iterator<Item=Self::Item> + 'a>;
 }
 
 /// Insecure variant of iteration over an attribute
@@ -75,7 +145,7 @@ pub trait InsecureIterableAttr<'a, Type: ?Sized> : Attr<Type> {
     type Item: 'a;
 
     /// Retrieval of an Iterator
-    fn iter(&self, i: Type) -> Result<Box<Iterator<Item=Self::Item> + 'a>>;
+    fn iter(&self, i: Type) -> Result<Box<dyn Iterator<Item=Self::Item> + 'a>>;
 }
 
 /// Recursive path traversal
@@ -84,10 +154,18 @@ pub trait InsecureIterableAttr<'a, Type: ?Sized> : Attr<Type> {
 /// but is needed to express bounds when accepting paths.
 pub trait Traverse<'a, 'b: 'a, X: 'b, Y: 'b> {
     /// implementation of the traversal for a specific path
-    #[inline]
     fn traverse(&'a self, val: X) -> Result<Y>;
 }
 
+/// Traversal of a path that cannot fail.
+///
+/// Paths implementing it usually implement `Traverse` as well, so they
+/// can be used wherever paths are expected.
+pub trait SecureTraverse<'a, 'b: 'a, X: 'b, Y: 'b> {
+    /// implementation of the traversal for a specific path
+    fn get(&'a self, val: X) -> Y;
+}
+
 /// The Identity is the end of a path and provides the point where
 /// input equals output and we start returning.
 /// It's necessary for recursive path traversal, but generally not
@@ -125,9 +203,10 @@ pub struct InsecurePath<Input, Output, A: InsecureAttr<Input>, Rest> {
 ///
 /// Paths are usually inferred and should not be directly used
 /// in user code.
-pub struct MapPath<A, R> {
+pub struct MapPath<Input, A: Attr<Input>, R> {
     attr: A,
     next: R,
+    phantom_x: PhantomData<Input>,
 }
 
 /// `retrieve` is the starting point of a path that always
@@ -139,7 +218,7 @@ pub fn retrieve<X, Z, A>(attr: A) -> Path<X, Z, A, Identity>
     where A: Attr<X>
 {
     Path {
-        attr: attr,
+        attr,
         next: Identity,
         phantom_x: PhantomData,
         phantom_z: PhantomData,
@@ -153,7 +232,7 @@ pub fn retrieve_insecure<X, Z, A>(attr: A) -> InsecurePath<X, Z, A, Identity>
     where A: InsecureAttr<X>
 {
     InsecurePath {
-        attr: attr,
+        attr,
         next: Identity,
         phantom_x: PhantomData,
         phantom_z: PhantomData,
@@ -176,17 +255,14 @@ impl<'a, 'b: 'a, X: 'b, Z: 'b, A: Attr<X>, R: Traverse<'a, 'b, A::Output, Z>> Tr
 impl<'a, 'b: 'a, X: 'b, Z: 'b, A: InsecureAttr<X>, R: Traverse<'a, 'b, A::Output, Z>> Traverse<'a, 'b, X, Z> for InsecurePath<X, Z, A, R> where <A as InsecureAttr<X>>::Output: 'b {
     #[inline]
     fn traverse(&'a self, obj: X) -> Result<Z> {
-        let val = self.attr.get(obj);
-        match val {
-            Ok(v) => self.next.traverse(v),
-            Err(_) => Err("Something went wrong".into())
-        }
+        let val = self.attr.get(obj)?;
+        self.next.traverse(val)
     }
 }
 
-impl<'a, X: 'a, Z: 'a, A: IterableAttr<'a, X>, R: Traverse<'a, 'a, A::Item, Z>> Traverse<'a, 'a, X, Box<Iterator<Item=Result<Z>> + 'a>> for MapPath<A, R> {
+impl<'a, X: 'a, Z: 'a, A: IterableAttr<'a, X>, R: Traverse<'a, 'a, A::Item, Z>> Traverse<'a, 'a, X, Box<dyn Iterator<Item=Result<Z>> + 'a>> for MapPath<X, A, R> {
     #[inline]
-    fn traverse(&'a self, obj: X) -> Result<Box<Iterator<Item=Result<Z>> + 'a>> {
+    fn traverse(&'a self, obj: X) -> Result<Box<dyn Iterator<Item=Result<Z>> + 'a>> {
         let iter = self.attr.iter(obj);
         let next = &self.next;
         let map = iter.map(move |v| next.traverse(v) );
@@ -198,11 +274,10 @@ impl<'a, 'b: 'a, X: 'b, Z: 'b, A: Attr<X>, R: Traverse<'a, 'b, A::Output, Z>> Pa
     /// Extends a path by another segment.
     ///
     /// This needs a retrieval that always succeds
-    pub fn from<NX: 'b, NY: 'b, NZ: 'b, NA>(self, attr: NA) -> Path<NX, NZ, NA, Self>
-        where A: Attr<NY, Output=Z>,
-              NA: Attr<NX, Output=NY> {
+    pub fn from<NX: 'b, NA>(self, attr: NA) -> Path<NX, Z, NA, Self>
+        where NA: Attr<NX, Output=X> {
         Path {
-            attr: attr,
+            attr,
             next: self,
             phantom_x: PhantomData,
             phantom_z: PhantomData,
@@ -212,11 +287,10 @@ impl<'a, 'b: 'a, X: 'b, Z: 'b, A: Attr<X>, R: Traverse<'a, 'b, A::Output, Z>> Pa
     /// Extends a path by another segment.
     ///
     /// This assumes that the retrieval cannot always succeed.
-    pub fn try<NX: 'a, NY: 'a, NZ: 'a, NA>(self, attr: NA) -> InsecurePath<NX, NZ, NA, Self>
-        where A: Attr<NY, Output=Z>,
-              NA: InsecureAttr<NX, Output=NY> {
+    pub fn try<NX: 'b, NA>(self, attr: NA) -> InsecurePath<NX, Z, NA, Self>
+        where NA: InsecureAttr<NX, Output=X> {
         InsecurePath {
-            attr: attr,
+            attr,
             next: self,
             phantom_x: PhantomData,
             phantom_z: PhantomData,
@@ -226,25 +300,25 @@ impl<'a, 'b: 'a, X: 'b, Z: 'b, A: Attr<X>, R: Traverse<'a, 'b, A::Output, Z>> Pa
     /// Extends a path by an iteration operation.
     ///
     /// This assumes that the iteration is always possible
-    pub fn mapped<NX: 'b, NY: 'b, NZ: 'b, NA>(self, attr: NA) -> MapPath<NA, Self>
+    pub fn mapped<NX: 'b, NY: 'b, NZ: 'b, NA>(self, attr: NA) -> MapPath<NX, NA, Self>
         where A: Attr<X>,
               R: Traverse<'a, 'b, A::Output, Z>,
               NA: IterableAttr<'a, NX, Item=NY>,
               Self: Traverse<'a, 'b, NY, NZ> {
         MapPath {
-            attr: attr,
+            attr,
             next: self,
+            phantom_x: PhantomData,
         }
     }
 }
 
 impl<'a, 'b: 'a, X: 'b, Z: 'b, A: InsecureAttr<X>, R: Traverse<'a, 'b, A::Output, Z>> InsecurePath<X, Z, A, R> where <A as InsecureAttr<X>>::Output: 'b {
     /// Extends a path that may fail by another segment that always succeeds.
-    pub fn from<NX: 'b, NY: 'b, NZ: 'b, NA>(self, attr: NA) -> Path<NX, NZ, NA, Self>
-        where A: InsecureAttr<NY, Output=Z>,
-              NA: Attr<NX, Output=NY> {
+    pub fn from<NX: 'b, NA>(self, attr: NA) -> Path<NX, Z, NA, Self>
+        where NA: Attr<NX, Output=X> {
         Path {
-            attr: attr,
+            attr,
             next: self,
             phantom_x: PhantomData,
             phantom_z: PhantomData,
@@ -252,11 +326,10 @@ impl<'a, 'b: 'a, X: 'b, Z: 'b, A: InsecureAttr<X>, R: Traverse<'a, 'b, A::Output
     }
 
     /// Extends a path that may fail by another segment that may fail.
-    pub fn try<NX: 'b, NY: 'b, NZ: 'b, NA>(self, attr: NA) -> InsecurePath<NX, NZ, NA, Self>
-        where A: InsecureAttr<NY, Output=Z>,
-              NA: InsecureAttr<NX, Output=NY> {
+    pub fn try<NX: 'b, NA>(self, attr: NA) -> InsecurePath<NX, Z, NA, Self>
+        where NA: InsecureAttr<NX, Output=X> {
         InsecurePath {
-            attr: attr,
+            attr,
             next: self,
             phantom_x: PhantomData,
             phantom_z: PhantomData,
@@ -264,28 +337,30 @@ impl<'a, 'b: 'a, X: 'b, Z: 'b, A: InsecureAttr<X>, R: Traverse<'a, 'b, A::Output
     }
 
     /// Extends a path that may fail by another segment that may fail.
-    pub fn mapped<NX: 'b, NY: 'b, NZ: 'b, NA>(self, attr: NA) -> MapPath<NA, Self>
+    pub fn mapped<NX: 'b, NY: 'b, NZ: 'b, NA>(self, attr: NA) -> MapPath<NX, NA, Self>
         where A: InsecureAttr<X>,
               R: Traverse<'a, 'b, A::Output, Z>,
               NA: IterableAttr<'a, NX, Item=NY>,
               Self: Traverse<'a, 'b, NY, NZ> {
         MapPath {
-            attr: attr,
+            attr,
             next: self,
+            phantom_x: PhantomData,
         }
     }
 }
 
-impl<A, R> MapPath<A, R> {
+impl<X, A: Attr<X>, R> MapPath<X, A, R> {
     /// Extends a mapped path by another segment that always succeeds
-    pub fn from<'a, 'b: 'a, X: 'b, Y: 'b, Z: 'b, NX: 'b, NY: 'b, NA>(self, attr: NA) -> Path<NX, Box<std::iter::Iterator<Item=Result<Z>> + 'a>, NA, Self>
-        where A: IterableAttr<'a, X, Item=Y>,
-              R: Traverse<'a, 'b, Y, Z>,
-              NA: Attr<NX, Output=NY>,
-              Self: Traverse<'a, 'b, NY, Box<std::iter::Iterator<Item=Result<Z>>>>
+    pub fn from<'a, Y: 'a, Z: 'a, NX: 'a, NA>(self, attr: NA) -> Path<NX, Box<dyn std::iter::Iterator<Item=Result<Z>> + 'a>, NA, Self>
+        where X: 'a,
+              A: IterableAttr<'a, X, Item=Y>,
+              R: Traverse<'a, 'a, Y, Z>,
+              NA: Attr<NX, Output=X>,
+              Self: Traverse<'a, 'a, X, Box<dyn std::iter::Iterator<Item=Result<Z>> + 'a>>
     {
         Path {
-            attr: attr,
+            attr,
             next: self,
             phantom_x: PhantomData,
             phantom_z: PhantomData,
@@ -0,0 +1,169 @@
+use std::any::Any;
+use std::fmt;
+
+use super::{Traverse, PathInfo, Result};
+
+/// Identifies a registered callback, for removal through `Observable::unwatch`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ObserverId(usize);
+
+/// A change reported to the callbacks of an `Observable`.
+///
+/// The notification describes the path that was set, which is not
+/// necessarily the watched one: a callback watching `data.email` is also
+/// notified when all of `data` is replaced, and then receives the old and
+/// new `data`, not the email addresses.
+pub struct Notification<'n> {
+    path: &'n str,
+    old: &'n dyn Any,
+    new: &'n dyn Any,
+}
+
+impl<'n> Notification<'n> {
+    /// The path that was changed, like `data.email`
+    pub fn path(&self) -> &str {
+        self.path
+    }
+
+    /// The previous value at `path()`, if it is of type `Y`
+    pub fn old_value<Y: Any>(&self) -> Option<&Y> {
+        self.old.downcast_ref()
+    }
+
+    /// The current value at `path()`, if it is of type `Y`
+    pub fn new_value<Y: Any>(&self) -> Option<&Y> {
+        self.new.downcast_ref()
+    }
+}
+
+impl<'n> fmt::Debug for Notification<'n> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Notification").field("path", &self.path).finish()
+    }
+}
+
+struct Observer {
+    id: ObserverId,
+    segments: Vec<String>,
+    callback: Box<dyn FnMut(&Notification)>,
+}
+
+/// A wrapper that notifies callbacks about changes made through paths.
+///
+/// Callbacks are registered on paths and are called for all changes that
+/// affect the value at that path: a callback watching `data` is notified
+/// about changes to `data.email`, and a callback watching `data.email` is
+/// notified when `data` is replaced. Paths are matched by the names of their
+/// attributes.
+///
+/// Changes must be made through `set` and `modify` to be noticed. Both
+/// take paths on mutable references and only notify if the value actually
+/// changed.
+///
+/// ```
+/// # use attr::{retrieve, Attr, Observable, Notification};
+/// # struct User { data: Data }
+/// # #[derive(Clone, PartialEq)] struct Data { email: String }
+/// # struct DataAttribute;
+/// # struct EmailAttribute;
+/// # impl<'a> Attr<&'a mut User> for DataAttribute { type Output = &'a mut Data; fn get(&self, u: &'a mut User) -> &'a mut Data { &mut u.data } fn name(&self) -> &str { "data" } }
+/// # impl<'a> Attr<&'a mut Data> for EmailAttribute { type Output = &'a mut String; fn get(&self, d: &'a mut Data) -> &'a mut String { &mut d.email } fn name(&self) -> &str { "email" } }
+/// # fn main() -> attr::Result<()> {
+/// # let user = User { data: Data { email: "flo@example.com".into() } };
+/// let mut user = Observable::new(user);
+/// user.watch(&retrieve(DataAttribute), |n: &Notification| println!("{} changed", n.path()));
+///
+/// user.set(&retrieve(EmailAttribute).from(DataAttribute), "new@example.com".to_string())?;
+/// # assert_eq!(user.get().data.email, "new@example.com");
+/// # Ok(())
+/// # }
+/// ```
+pub struct Observable<T> {
+    value: T,
+    observers: Vec<Observer>,
+    next_id: usize,
+}
+
+impl<T> Observable<T> {
+    /// Wraps `value`.
+    pub fn new(value: T) -> Observable<T> {
+        Observable { value, observers: Vec::new(), next_id: 0 }
+    }
+
+    /// The wrapped value
+    pub fn get(&self) -> &T {
+        &self.value
+    }
+
+    /// Unwraps the value, dropping all callbacks.
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+
+    /// Calls `callback` for all changes at, below or above `path`.
+    pub fn watch<'a, 'b: 'a, P, Y: 'b, F>(&mut self, path: &'a P, callback: F) -> ObserverId
+        where P: Traverse<'a, 'b, &'b mut T, Y> + PathInfo,
+              T: 'b,
+              F: FnMut(&Notification) + 'static
+    {
+        let id = ObserverId(self.next_id);
+        self.next_id += 1;
+        self.observers.push(Observer {
+            id,
+            segments: path.segments().iter().map(|s| s.name.to_owned()).collect(),
+            callback: Box::new(callback),
+        });
+        id
+    }
+
+    /// Removes a callback. Returns whether it was registered.
+    pub fn unwatch(&mut self, id: ObserverId) -> bool {
+        let before = self.observers.len();
+        self.observers.retain(|o| o.id != id);
+        self.observers.len() != before
+    }
+
+    /// Replaces the value at `path` and returns the previous one.
+    pub fn set<'a, 'b: 'a, P, Y>(&'b mut self, path: &'a P, value: Y) -> Result<Y>
+        where P: Traverse<'a, 'b, &'b mut T, &'b mut Y> + PathInfo,
+              Y: Any + PartialEq
+    {
+        let target = path.traverse(&mut self.value)?;
+        let old = std::mem::replace(target, value);
+        if old != *target {
+            notify(&mut self.observers, path, &old, &*target);
+        }
+        Ok(old)
+    }
+
+    /// Changes the value at `path` in place.
+    pub fn modify<'a, 'b: 'a, P, Y, F>(&'b mut self, path: &'a P, f: F) -> Result<()>
+        where P: Traverse<'a, 'b, &'b mut T, &'b mut Y> + PathInfo,
+              Y: Any + PartialEq + Clone,
+              F: FnOnce(&mut Y)
+    {
+        let target = path.traverse(&mut self.value)?;
+        let old = target.clone();
+        f(target);
+        if old != *target {
+            notify(&mut self.observers, path, &old, &*target);
+        }
+        Ok(())
+    }
+}
+
+fn notify<P: PathInfo>(observers: &mut [Observer], path: &P, old: &dyn Any, new: &dyn Any) {
+    let segments = path.segments();
+    let rendered = path.breadcrumb(None);
+    let notification = Notification { path: &rendered, old, new };
+
+    for observer in observers {
+        // The shorter path is a prefix of the longer one: either the watched
+        // value contains the changed one or the other way around.
+        let watched = observer.segments.iter().zip(&segments).all(|(w, s)| w == s.name);
+
+        if watched {
+            (observer.callback)(&notification);
+        }
+    }
+}
@@ -0,0 +1,36 @@
+use super::{InsecureAttr, InsecurePath, Traverse, PathInfo, Segment, Result};
+
+/// A path that distinguishes absent values from malformed data.
+///
+/// Failures of kind `ErrorKind::Missing` are returned as `Ok(None)`,
+/// all other failures are passed on.
+///
+/// Paths are usually inferred and should not be directly used
+/// in user code.
+pub struct OptionalPath<P> {
+    path: P,
+}
+
+impl<'a, 'b: 'a, X: 'b, Y: 'b, P: Traverse<'a, 'b, X, Y>> Traverse<'a, 'b, X, Option<Y>> for OptionalPath<P> {
+    #[inline]
+    fn traverse(&'a self, obj: X) -> Result<Option<Y>> {
+        match self.path.traverse(obj) {
+            Ok(v) => Ok(Some(v)),
+            Err(ref e) if e.is_missing() => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+impl<P: PathInfo> PathInfo for OptionalPath<P> {
+    fn push_segments<'a>(&'a self, segments: &mut Vec<Segment<'a>>) {
+        self.path.push_segments(segments);
+    }
+}
+
+impl<X, Z, A: InsecureAttr<X>, R> InsecurePath<X, Z, A, R> {
+    /// Returns `None` instead of failing if a value along the path is absent.
+    pub fn optional(self) -> OptionalPath<Self> {
+        OptionalPath { path: self }
+    }
+}
@@ -0,0 +1,87 @@
+use std::marker::PhantomData;
+
+use rayon::iter::plumbing::{Consumer, ProducerCallback, UnindexedConsumer};
+use rayon::prelude::*;
+
+use super::{Attr, InsecureAttr, IterableAttr, Path, InsecurePath, MapPath, Traverse, Result};
+
+/// Parallel traversal of paths containing an iteration.
+///
+/// The items of the iteration are collected first, the rest of the path
+/// is then applied to them on the rayon thread pool. Segments before the
+/// iteration are traversed on the calling thread.
+pub trait ParTraverse<'a, 'b: 'a, X: 'b, Z: 'b> {
+    /// The parallel iterator over the results
+    type Iter: IndexedParallelIterator<Item=Result<Z>> + 'a;
+
+    /// implementation of the parallel traversal for a specific path
+    fn par_traverse(&'a self, val: X) -> Result<Self::Iter>;
+}
+
+/// The results of traversing a `MapPath` in parallel, in the order of the items.
+pub struct ParMap<'a, Y, Z, R: 'a> {
+    items: Vec<Y>,
+    next: &'a R,
+    phantom_z: PhantomData<fn() -> Z>,
+}
+
+impl<'a, Y: Send + 'a, Z: Send + 'a, R: Traverse<'a, 'a, Y, Z> + Sync> ParallelIterator for ParMap<'a, Y, Z, R> {
+    type Item = Result<Z>;
+
+    fn drive_unindexed<C>(self, consumer: C) -> C::Result
+        where C: UnindexedConsumer<Self::Item>
+    {
+        let next = self.next;
+        self.items.into_par_iter().map(move |item| next.traverse(item)).drive_unindexed(consumer)
+    }
+
+    fn opt_len(&self) -> Option<usize> {
+        Some(self.items.len())
+    }
+}
+
+impl<'a, Y: Send + 'a, Z: Send + 'a, R: Traverse<'a, 'a, Y, Z> + Sync> IndexedParallelIterator for ParMap<'a, Y, Z, R> {
+    fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    fn drive<C: Consumer<Self::Item>>(self, consumer: C) -> C::Result {
+        let next = self.next;
+        self.items.into_par_iter().map(move |item| next.traverse(item)).drive(consumer)
+    }
+
+    fn with_producer<CB: ProducerCallback<Self::Item>>(self, callback: CB) -> CB::Output {
+        let next = self.next;
+        self.items.into_par_iter().map(move |item| next.traverse(item)).with_producer(callback)
+    }
+}
+
+impl<'a, X: 'a, Z: Send + 'a, A, R: 'a> ParTraverse<'a, 'a, X, Z> for MapPath<X, A, R>
+    where A: IterableAttr<'a, X>,
+          A::Item: Send,
+          R: Traverse<'a, 'a, A::Item, Z> + Sync
+{
+    type Iter = ParMap<'a, A::Item, Z, R>;
+
+    fn par_traverse(&'a self, obj: X) -> Result<Self::Iter> {
+        Ok(ParMap { items: self.attr.iter(obj).collect(), next: &self.next, phantom_z: PhantomData })
+    }
+}
+
+impl<'a, 'b: 'a, X: 'b, Y, Z: 'b, A: Attr<X>, R: ParTraverse<'a, 'b, A::Output, Z>> ParTraverse<'a, 'b, X, Z> for Path<X, Y, A, R> where <A as Attr<X>>::Output: 'b {
+    type Iter = R::Iter;
+
+    #[inline]
+    fn par_traverse(&'a self, obj: X) -> Result<R::Iter> {
+        self.next.par_traverse(self.attr.get(obj))
+    }
+}
+
+impl<'a, 'b: 'a, X: 'b, Y, Z: 'b, A: InsecureAttr<X>, R: ParTraverse<'a, 'b, A::Output, Z>> ParTraverse<'a, 'b, X, Z> for InsecurePath<X, Y, A, R> where <A as InsecureAttr<X>>::Output: 'b {
+    type Iter = R::Iter;
+
+    #[inline]
+    fn par_traverse(&'a self, obj: X) -> Result<R::Iter> {
+        self.next.par_traverse(self.attr.get(obj)?)
+    }
+}
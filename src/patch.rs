@@ -0,0 +1,284 @@
+//! JSON Patch (RFC 6902) for `serde_json::Value`s, available with the `json` feature.
+//!
+//! Patches can be deserialized from patch documents, applied to values, and
+//! generated from the differences between two values.
+//!
+//! ```
+//! # #[macro_use] extern crate serde_json;
+//! # extern crate attr;
+//! # use attr::patch::{diff, Patch};
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! # let mut document = json!({ "user": { "data": { "email": "a@example.com" } } });
+//! let patch: Patch = serde_json::from_str(r#"[{ "op": "replace", "path": "/user/data/email", "value": "b@example.com" }]"#)?;
+//! patch.apply(&mut document)?;
+//! # assert_eq!(document["user"]["data"]["email"], "b@example.com");
+//! # let (mut before, after) = (json!({ "a": [1, 2] }), json!({ "a": [2], "b": true }));
+//!
+//! diff(&before, &after).apply(&mut before)?;
+//! assert_eq!(before, after);
+//! # Ok(())
+//! # }
+//! ```
+
+use std::fmt;
+
+use serde::{Serialize, Deserialize};
+use serde_json::Value;
+
+use super::json::Pointer;
+use super::{Error, Result, parse_index};
+
+/// A single patch operation.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "lowercase")]
+pub enum Operation {
+    /// Adds a value to an object or inserts it into an array
+    Add {
+        /// Where to add the value
+        path: Pointer,
+        /// The value to add
+        value: Value,
+    },
+    /// Removes a value
+    Remove {
+        /// The value to remove
+        path: Pointer,
+    },
+    /// Replaces an existing value
+    Replace {
+        /// The value to replace
+        path: Pointer,
+        /// The new value
+        value: Value,
+    },
+    /// Removes a value and adds it at another location
+    Move {
+        /// The value to move
+        from: Pointer,
+        /// Where to add the value
+        path: Pointer,
+    },
+    /// Adds a copy of a value at another location
+    Copy {
+        /// The value to copy
+        from: Pointer,
+        /// Where to add the value
+        path: Pointer,
+    },
+    /// Checks that a value is equal to the given one
+    Test {
+        /// The value to check
+        path: Pointer,
+        /// The expected value
+        value: Value,
+    },
+}
+
+impl Operation {
+    /// The name of the operation, as used in patch documents
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Operation::Add { .. } => "add",
+            Operation::Remove { .. } => "remove",
+            Operation::Replace { .. } => "replace",
+            Operation::Move { .. } => "move",
+            Operation::Copy { .. } => "copy",
+            Operation::Test { .. } => "test",
+        }
+    }
+
+    /// The location the operation targets
+    pub fn path(&self) -> &Pointer {
+        match *self {
+            Operation::Add { ref path, .. } |
+            Operation::Remove { ref path } |
+            Operation::Replace { ref path, .. } |
+            Operation::Move { ref path, .. } |
+            Operation::Copy { ref path, .. } |
+            Operation::Test { ref path, .. } => path,
+        }
+    }
+
+    /// Applies the operation to `doc`.
+    ///
+    /// On failure, `doc` may be left partially modified, use `Patch::apply` for atomic application.
+    pub fn apply(&self, doc: &mut Value) -> Result<()> {
+        match *self {
+            Operation::Add { ref path, ref value } => add(doc, path, value.clone()),
+            Operation::Remove { ref path } => remove(doc, path).map(|_| ()),
+            Operation::Replace { ref path, ref value } => {
+                *path.get_mut(doc)? = value.clone();
+                Ok(())
+            },
+            Operation::Move { ref from, ref path } => {
+                if from.is_proper_prefix_of(path) {
+                    return Err(Error::invalid(format!("cannot move {} into itself", from)));
+                }
+                let value = remove(doc, from)?;
+                add(doc, path, value)
+            },
+            Operation::Copy { ref from, ref path } => {
+                let value = from.get(doc)?.clone();
+                add(doc, path, value)
+            },
+            Operation::Test { ref path, ref value } => {
+                if equal(path.get(doc)?, value) {
+                    Ok(())
+                } else {
+                    Err(Error::invalid(format!("value is not {}", value)))
+                }
+            },
+        }
+    }
+}
+
+impl fmt::Display for Operation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {}", self.name(), self.path())
+    }
+}
+
+/// Equality as required for `test` operations (RFC 6902, section 4.6):
+/// numbers are equal if their values are, so `1` equals `1.0`.
+fn equal(left: &Value, right: &Value) -> bool {
+    match (left, right) {
+        (Value::Number(l), Value::Number(r)) => {
+            let integer = |n: &serde_json::Number| n.as_i64().map(i128::from).or_else(|| n.as_u64().map(i128::from));
+            match (integer(l), integer(r)) {
+                (Some(l), Some(r)) => l == r,
+                _ => l.as_f64() == r.as_f64(),
+            }
+        },
+        (Value::Array(l), Value::Array(r)) => l.len() == r.len() && l.iter().zip(r).all(|(l, r)| equal(l, r)),
+        (Value::Object(l), Value::Object(r)) => {
+            l.len() == r.len() && l.iter().all(|(k, l)| match r.get(k) {
+                Some(r) => equal(l, r),
+                None => false,
+            })
+        },
+        _ => left == right,
+    }
+}
+
+fn add(doc: &mut Value, path: &Pointer, value: Value) -> Result<()> {
+    let (parent, last) = match path.split_last() {
+        Some(split) => split,
+        None => {
+            *doc = value;
+            return Ok(());
+        }
+    };
+
+    match *parent.get_mut(doc)? {
+        Value::Object(ref mut m) => {
+            m.insert(last.to_owned(), value);
+            Ok(())
+        },
+        Value::Array(ref mut v) => {
+            let index = if last == "-" { v.len() } else { parse_index(last)? };
+            if index > v.len() {
+                return Err(Error::missing(format!("index {} is out of bounds", index)));
+            }
+            v.insert(index, value);
+            Ok(())
+        },
+        _ => Err(Error::invalid(format!("cannot add {} to a value that is neither object nor array", last))),
+    }
+}
+
+fn remove(doc: &mut Value, path: &Pointer) -> Result<Value> {
+    let (parent, last) = path.split_last().ok_or_else(|| Error::invalid("cannot remove the whole document"))?;
+
+    match *parent.get_mut(doc)? {
+        Value::Object(ref mut m) => m.remove(last).ok_or_else(|| Error::missing(format!("{} is not present", last))),
+        Value::Array(ref mut v) => {
+            let index = parse_index(last)?;
+            if index >= v.len() {
+                return Err(Error::missing(format!("index {} is out of bounds", index)));
+            }
+            Ok(v.remove(index))
+        },
+        _ => Err(Error::invalid(format!("cannot remove {} from a value that is neither object nor array", last))),
+    }
+}
+
+/// A JSON Patch document, a list of operations applied in order.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Patch(pub Vec<Operation>);
+
+impl Patch {
+    /// Creates an empty patch.
+    pub fn new() -> Patch {
+        Patch::default()
+    }
+
+    /// The operations of the patch
+    pub fn operations(&self) -> &[Operation] {
+        &self.0
+    }
+
+    /// Whether the patch has no operations
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Applies all operations to `doc`.
+    ///
+    /// Application is atomic: if an operation fails, `doc` is left untouched.
+    /// The error names the failing operation and location, and records
+    /// the position of the operation in the patch as its index.
+    pub fn apply(&self, doc: &mut Value) -> Result<()> {
+        let mut patched = doc.clone();
+
+        for (i, operation) in self.0.iter().enumerate() {
+            operation.apply(&mut patched)
+                .map_err(|e| Error::new(e.kind(), format!("{}: {}", operation, e.message())).with_index(i))?;
+        }
+
+        *doc = patched;
+        Ok(())
+    }
+}
+
+/// Generates a patch turning `old` into `new`.
+///
+/// Objects are compared member by member and arrays element by element,
+/// all other changes are expressed as replacements.
+pub fn diff(old: &Value, new: &Value) -> Patch {
+    let mut operations = Vec::new();
+    diff_into(&Pointer::root(), old, new, &mut operations);
+    Patch(operations)
+}
+
+fn diff_into(path: &Pointer, old: &Value, new: &Value, operations: &mut Vec<Operation>) {
+    match (old, new) {
+        (Value::Object(old), Value::Object(new)) => {
+            for (key, value) in old {
+                match new.get(key) {
+                    Some(other) => diff_into(&path.child(key.as_str()), value, other, operations),
+                    None => operations.push(Operation::Remove { path: path.child(key.as_str()) }),
+                }
+            }
+            for (key, value) in new {
+                if !old.contains_key(key) {
+                    operations.push(Operation::Add { path: path.child(key.as_str()), value: value.clone() });
+                }
+            }
+        },
+        (Value::Array(old), Value::Array(new)) => {
+            for (i, (value, other)) in old.iter().zip(new.iter()).enumerate() {
+                diff_into(&path.child(i.to_string()), value, other, operations);
+            }
+            for (i, value) in new.iter().enumerate().skip(old.len()) {
+                operations.push(Operation::Add { path: path.child(i.to_string()), value: value.clone() });
+            }
+            for i in (new.len()..old.len()).rev() {
+                operations.push(Operation::Remove { path: path.child(i.to_string()) });
+            }
+        },
+        (old, new) => if old != new {
+            operations.push(Operation::Replace { path: path.clone(), value: new.clone() });
+        },
+    }
+}
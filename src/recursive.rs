@@ -0,0 +1,218 @@
+use std::collections::HashSet;
+use std::collections::VecDeque;
+use std::marker::PhantomData;
+
+use super::{Attr, InsecureAttr, IterableAttr, Traverse, Path, PathInfo, Segment, SegmentKind, Error, Result};
+
+/// A step from one node of a recursive data structure to the nodes
+/// directly reachable from it.
+///
+/// This is implemented by the wrappers constructed through `recursive`
+/// and `recursive_insecure` and should rarely need to be implemented yourself.
+pub trait Descend<'a, T: 'a> {
+    /// The name of the attribute used for the step
+    fn name(&self) -> &str;
+    /// All nodes directly reachable from `node`
+    fn descend(&self, node: &'a T) -> Result<Box<dyn Iterator<Item=&'a T> + 'a>>;
+}
+
+/// Descends through an `IterableAttr`, for example a list of children.
+pub struct Children<A>(A);
+
+/// Descends through an `InsecureAttr`, for example an optional parent.
+///
+/// An absent value (`ErrorKind::Missing`) ends the chain at the current node,
+/// other failures are reported.
+pub struct Link<A>(A);
+
+impl<'a, T: 'a, A: IterableAttr<'a, &'a T, Item=&'a T>> Descend<'a, T> for Children<A> {
+    fn name(&self) -> &str {
+        self.0.name()
+    }
+
+    fn descend(&self, node: &'a T) -> Result<Box<dyn Iterator<Item=&'a T> + 'a>> {
+        Ok(self.0.iter(node))
+    }
+}
+
+impl<'a, T: 'a, A: InsecureAttr<&'a T, Output=&'a T>> Descend<'a, T> for Link<A> {
+    fn name(&self) -> &str {
+        self.0.name()
+    }
+
+    fn descend(&self, node: &'a T) -> Result<Box<dyn Iterator<Item=&'a T> + 'a>> {
+        match self.0.get(node) {
+            Ok(next) => Ok(Box::new(Some(next).into_iter())),
+            Err(ref e) if e.is_missing() => Ok(Box::new(None.into_iter())),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+/// The order in which a `RecursivePath` visits nodes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Order {
+    /// Visit all descendants of a node before its next sibling
+    DepthFirst,
+    /// Visit all nodes of one level before the next level
+    BreadthFirst,
+}
+
+/// A path that repeatedly applies an attribute, starting at the
+/// node it is given, and yields every node reachable that way.
+///
+/// Every node is only visited once, so cyclic structures terminate.
+/// The start node itself is yielded first, at depth 0.
+///
+/// Paths are usually inferred and should not be directly used
+/// in user code.
+pub struct RecursivePath<D> {
+    step: D,
+    // The name of the step, looked up through `Descend` without
+    // the node type, which `PathInfo` cannot name.
+    name: fn(&D) -> &str,
+    order: Order,
+    max_depth: Option<usize>,
+}
+
+/// `recursive` is the starting point of a path walking a recursive
+/// structure through an attribute yielding multiple nodes, such as `children`.
+pub fn recursive<'a, T: 'a, A>(attr: A) -> RecursivePath<Children<A>>
+    where A: IterableAttr<'a, &'a T, Item=&'a T>
+{
+    RecursivePath {
+        step: Children(attr),
+        name: |step| Descend::<'a, T>::name(step),
+        order: Order::DepthFirst,
+        max_depth: None,
+    }
+}
+
+/// `recursive_insecure` is the starting point of a path walking a recursive
+/// structure through an attribute that might not be present, such as `parent`.
+pub fn recursive_insecure<'a, T: 'a, A>(attr: A) -> RecursivePath<Link<A>>
+    where A: InsecureAttr<&'a T, Output=&'a T>
+{
+    RecursivePath {
+        step: Link(attr),
+        name: |step| Descend::<'a, T>::name(step),
+        order: Order::DepthFirst,
+        max_depth: None,
+    }
+}
+
+/// Described as a single iterating segment named after the attribute
+/// of the step, like `children[*]`.
+impl<D> PathInfo for RecursivePath<D> {
+    fn push_segments<'a>(&'a self, segments: &mut Vec<Segment<'a>>) {
+        segments.push(Segment { name: (self.name)(&self.step), kind: SegmentKind::Iterable });
+    }
+}
+
+impl<D> RecursivePath<D> {
+    /// Visit nodes depth-first (pre-order). This is the default.
+    pub fn depth_first(self) -> Self {
+        self.order(Order::DepthFirst)
+    }
+
+    /// Visit nodes breadth-first, level by level.
+    pub fn breadth_first(self) -> Self {
+        self.order(Order::BreadthFirst)
+    }
+
+    /// Sets the order in which nodes are visited.
+    pub fn order(mut self, order: Order) -> Self {
+        self.order = order;
+        self
+    }
+
+    /// Stops descending below the given depth. The start node has depth 0.
+    pub fn max_depth(mut self, depth: usize) -> Self {
+        self.max_depth = Some(depth);
+        self
+    }
+
+    /// Extends a recursive path by a segment leading to the start node.
+    pub fn from<'a, T: 'a, NX: 'a, NA>(self, attr: NA) -> Path<NX, Box<dyn Iterator<Item=Result<&'a T>> + 'a>, NA, Self>
+        where D: Descend<'a, T>,
+              NA: Attr<NX, Output=&'a T>
+    {
+        Path {
+            attr,
+            next: self,
+            phantom_x: PhantomData,
+            phantom_z: PhantomData,
+        }
+    }
+}
+
+/// Iterator over the nodes reached by a `RecursivePath`.
+pub struct Walk<'a, T: 'a, D: 'a> {
+    step: &'a D,
+    order: Order,
+    max_depth: Option<usize>,
+    pending: VecDeque<(&'a T, usize)>,
+    visited: HashSet<*const T>,
+    failed: Option<Error>,
+}
+
+impl<'a, T: 'a, D: Descend<'a, T>> Iterator for Walk<'a, T, D> {
+    type Item = Result<&'a T>;
+
+    fn next(&mut self) -> Option<Result<&'a T>> {
+        if let Some(e) = self.failed.take() {
+            return Some(Err(e));
+        }
+
+        loop {
+            let (node, depth) = match self.order {
+                Order::DepthFirst => self.pending.pop_back()?,
+                Order::BreadthFirst => self.pending.pop_front()?,
+            };
+
+            if !self.visited.insert(node as *const T) {
+                continue;
+            }
+
+            let descend = match self.max_depth {
+                Some(max) => depth < max,
+                None => true,
+            };
+
+            if descend {
+                match self.step.descend(node) {
+                    Ok(children) => {
+                        let children = children.map(|c| (c, depth + 1));
+                        match self.order {
+                            Order::DepthFirst => {
+                                let children = children.collect::<Vec<_>>();
+                                self.pending.extend(children.into_iter().rev());
+                            }
+                            Order::BreadthFirst => self.pending.extend(children),
+                        }
+                    }
+                    Err(e) => self.failed = Some(e),
+                }
+            }
+
+            return Some(Ok(node));
+        }
+    }
+}
+
+impl<'a, T: 'a, D: Descend<'a, T>> Traverse<'a, 'a, &'a T, Box<dyn Iterator<Item=Result<&'a T>> + 'a>> for RecursivePath<D> {
+    #[inline]
+    fn traverse(&'a self, obj: &'a T) -> Result<Box<dyn Iterator<Item=Result<&'a T>> + 'a>> {
+        let mut pending = VecDeque::new();
+        pending.push_back((obj, 0));
+
+        Ok(Box::new(Walk {
+            step: &self.step,
+            order: self.order,
+            max_depth: self.max_depth,
+            pending,
+            visited: HashSet::new(),
+            failed: None,
+        }))
+    }
+}
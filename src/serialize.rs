@@ -0,0 +1,833 @@
+//! Conversion of `Serialize` types into `Value`s, and extraction of single
+//! fields from them, available with the `serde` feature.
+
+use std::collections::BTreeMap;
+use std::convert::TryInto;
+use std::fmt;
+
+use serde::ser::{self, Serialize, Serializer};
+use serde::de::{self, Deserialize, Deserializer, Visitor, SeqAccess, MapAccess};
+
+use super::{InsecureAttr, Value, Error, Result, parse_index};
+
+/// Converts any `Serialize` type into a `Value`.
+///
+/// Structs and maps become maps, sequences and tuples become lists.
+/// Enums are represented like `serde_json` does: unit variants as strings,
+/// all other variants as maps with the variant name as the only key. Map
+/// keys must be strings, numbers, booleans or characters.
+///
+/// Together with `value::Key` and friends, this allows traversing types
+/// that have no attributes of their own:
+///
+/// ```
+/// # #[macro_use] extern crate serde;
+/// # extern crate attr;
+/// # use attr::{retrieve_insecure, Traverse, Value};
+/// # use attr::value::{to_value, Key};
+/// # #[derive(Serialize)] struct User { data: Data }
+/// # #[derive(Serialize)] struct Data { email: String }
+/// # fn main() -> attr::Result<()> {
+/// # let user = User { data: Data { email: "flo@example.com".into() } };
+/// let value = to_value(&user)?;
+/// let email = retrieve_insecure(Key::new("email")).try(Key::new("data")).traverse(&value)?;
+/// # assert_eq!(email, &Value::from("flo@example.com"));
+/// # Ok(())
+/// # }
+/// ```
+pub fn to_value<T: Serialize + ?Sized>(value: &T) -> Result<Value> {
+    value.serialize(ValueSerializer)
+}
+
+struct ValueSerializer;
+
+fn variant(name: &'static str, value: Value) -> Value {
+    let mut map = BTreeMap::new();
+    map.insert(name.to_owned(), value);
+    Value::Map(map)
+}
+
+impl Serializer for ValueSerializer {
+    type Ok = Value;
+    type Error = Error;
+
+    type SerializeSeq = ListSerializer;
+    type SerializeTuple = ListSerializer;
+    type SerializeTupleStruct = ListSerializer;
+    type SerializeTupleVariant = ListSerializer;
+    type SerializeMap = MapSerializer;
+    type SerializeStruct = MapSerializer;
+    type SerializeStructVariant = MapSerializer;
+
+    fn serialize_bool(self, v: bool) -> Result<Value> {
+        Ok(Value::Bool(v))
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<Value> {
+        Ok(Value::I64(v.into()))
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<Value> {
+        Ok(Value::I64(v.into()))
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<Value> {
+        Ok(Value::I64(v.into()))
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<Value> {
+        Ok(Value::I64(v))
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<Value> {
+        if let Ok(v) = v.try_into() {
+            Ok(Value::I64(v))
+        } else if let Ok(v) = v.try_into() {
+            Ok(Value::U64(v))
+        } else {
+            Err(Error::invalid(format!("{} does not fit into 64 bits", v)))
+        }
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<Value> {
+        Ok(Value::U64(v.into()))
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<Value> {
+        Ok(Value::U64(v.into()))
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<Value> {
+        Ok(Value::U64(v.into()))
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<Value> {
+        Ok(Value::U64(v))
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<Value> {
+        v.try_into().map(Value::U64).map_err(|_| Error::invalid(format!("{} does not fit into 64 bits", v)))
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<Value> {
+        Ok(Value::F64(v.into()))
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<Value> {
+        Ok(Value::F64(v))
+    }
+
+    fn serialize_char(self, v: char) -> Result<Value> {
+        Ok(Value::String(v.to_string()))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Value> {
+        Ok(Value::String(v.to_owned()))
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<Value> {
+        Ok(Value::List(v.iter().map(|&b| Value::U64(b.into())).collect()))
+    }
+
+    fn serialize_none(self) -> Result<Value> {
+        Ok(Value::Null)
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<Value> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Value> {
+        Ok(Value::Null)
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Value> {
+        Ok(Value::Null)
+    }
+
+    fn serialize_unit_variant(self, _name: &'static str, _index: u32, variant: &'static str) -> Result<Value> {
+        Ok(Value::String(variant.to_owned()))
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(self, _name: &'static str, value: &T) -> Result<Value> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(self, _name: &'static str, _index: u32, name: &'static str, value: &T) -> Result<Value> {
+        Ok(variant(name, value.serialize(self)?))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<ListSerializer> {
+        Ok(ListSerializer { items: Vec::with_capacity(len.unwrap_or(0)), variant: None })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<ListSerializer> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(self, _name: &'static str, len: usize) -> Result<ListSerializer> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(self, _name: &'static str, _index: u32, name: &'static str, len: usize) -> Result<ListSerializer> {
+        Ok(ListSerializer { items: Vec::with_capacity(len), variant: Some(name) })
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<MapSerializer> {
+        Ok(MapSerializer { members: BTreeMap::new(), key: None, variant: None })
+    }
+
+    fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<MapSerializer> {
+        self.serialize_map(None)
+    }
+
+    fn serialize_struct_variant(self, _name: &'static str, _index: u32, name: &'static str, _len: usize) -> Result<MapSerializer> {
+        Ok(MapSerializer { members: BTreeMap::new(), key: None, variant: Some(name) })
+    }
+}
+
+struct ListSerializer {
+    items: Vec<Value>,
+    variant: Option<&'static str>,
+}
+
+impl ListSerializer {
+    fn push<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        self.items.push(value.serialize(ValueSerializer)?);
+        Ok(())
+    }
+
+    fn finish(self) -> Result<Value> {
+        let list = Value::List(self.items);
+        Ok(match self.variant {
+            Some(name) => variant(name, list),
+            None => list,
+        })
+    }
+}
+
+impl ser::SerializeSeq for ListSerializer {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Value> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTuple for ListSerializer {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Value> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTupleStruct for ListSerializer {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Value> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTupleVariant for ListSerializer {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Value> {
+        self.finish()
+    }
+}
+
+struct MapSerializer {
+    members: BTreeMap<String, Value>,
+    key: Option<String>,
+    variant: Option<&'static str>,
+}
+
+impl MapSerializer {
+    fn insert<T: Serialize + ?Sized>(&mut self, key: String, value: &T) -> Result<()> {
+        let value = value.serialize(ValueSerializer)?;
+        self.members.insert(key, value);
+        Ok(())
+    }
+
+    fn finish(self) -> Result<Value> {
+        let map = Value::Map(self.members);
+        Ok(match self.variant {
+            Some(name) => variant(name, map),
+            None => map,
+        })
+    }
+}
+
+fn key(value: Value) -> Result<String> {
+    match value {
+        Value::String(s) => Ok(s),
+        Value::Bool(b) => Ok(b.to_string()),
+        Value::I64(i) => Ok(i.to_string()),
+        Value::U64(u) => Ok(u.to_string()),
+        _ => Err(Error::invalid("map keys must be strings, numbers, booleans or characters")),
+    }
+}
+
+impl ser::SerializeMap for MapSerializer {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<()> {
+        self.key = Some(self::key(key.serialize(ValueSerializer)?)?);
+        Ok(())
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        let key = self.key.take().ok_or_else(|| Error::invalid("map value serialized before its key"))?;
+        self.insert(key, value)
+    }
+
+    fn end(self) -> Result<Value> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeStruct for MapSerializer {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, key: &'static str, value: &T) -> Result<()> {
+        self.insert(key.to_owned(), value)
+    }
+
+    fn end(self) -> Result<Value> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeStructVariant for MapSerializer {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, key: &'static str, value: &T) -> Result<()> {
+        self.insert(key.to_owned(), value)
+    }
+
+    fn end(self) -> Result<Value> {
+        self.finish()
+    }
+}
+
+/// Retrieves a field of any `Serialize` type by its dotted path, like
+/// `orders.1.total`.
+///
+/// The value is walked through its `Serialize` implementation, without
+/// converting it: members and elements off the path are skipped, only the
+/// field itself is converted into a `Value`. Segments name struct fields,
+/// map keys and enum variants, or index sequences and tuples, with the
+/// same representation and errors as `to_value` followed by `Key`s:
+///
+/// ```
+/// # #[macro_use] extern crate serde;
+/// # extern crate attr;
+/// # use attr::{retrieve_insecure, Attr, InsecureAttr, Traverse, Value};
+/// # use attr::value::Field;
+/// # #[derive(Serialize)] struct User { data: Data, orders: Vec<Order> }
+/// # #[derive(Serialize)] struct Data { email: String }
+/// # #[derive(Serialize)] struct Order { total: u32 }
+/// # struct DataAttribute;
+/// # impl<'a> Attr<&'a User> for DataAttribute { type Output = &'a Data; fn get(&self, u: &'a User) -> &'a Data { &u.data } fn name(&self) -> &str { "data" } }
+/// # fn main() -> attr::Result<()> {
+/// # let user = User { data: Data { email: "flo@example.com".into() }, orders: vec![Order { total: 5 }, Order { total: 12 }] };
+/// let total = Field::new("orders.1.total").get(&user)?;
+/// let email = retrieve_insecure(Field::new("email")).from(DataAttribute).traverse(&user)?;
+/// # assert_eq!((total, email), (Value::from(12u32), Value::from("flo@example.com")));
+/// # Ok(())
+/// # }
+/// ```
+///
+/// The empty path selects the whole value.
+pub struct Field {
+    name: String,
+    path: Vec<String>,
+}
+
+impl Field {
+    /// Creates an attribute retrieving the field at the dotted `path`.
+    pub fn new(path: &str) -> Field {
+        if path.is_empty() {
+            return Field::from_segments(Vec::<String>::new());
+        }
+        Field::from_segments(path.split('.'))
+    }
+
+    /// Creates an attribute retrieving the field at `segments`, which may contain dots.
+    pub fn from_segments<I: IntoIterator<Item=S>, S: Into<String>>(segments: I) -> Field {
+        let path = segments.into_iter().map(Into::into).collect::<Vec<String>>();
+        Field { name: path.join("."), path }
+    }
+
+    /// The segments of the path
+    pub fn segments(&self) -> &[String] {
+        &self.path
+    }
+}
+
+impl<'a, T: Serialize + ?Sized> InsecureAttr<&'a T> for Field {
+    type Output = Value;
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn get(&self, i: &'a T) -> Result<Value> {
+        select(i, &self.path)
+    }
+}
+
+fn select<T: Serialize + ?Sized>(value: &T, path: &[String]) -> Result<Value> {
+    if path.is_empty() {
+        value.serialize(ValueSerializer)
+    } else {
+        value.serialize(Extractor { path })
+    }
+}
+
+fn absent(name: &str) -> Error {
+    Error::missing(format!("{} is not present", name))
+}
+
+/// Walks a value along a non-empty path.
+struct Extractor<'p> {
+    path: &'p [String],
+}
+
+impl<'p> Extractor<'p> {
+    fn scalar(self) -> Result<Value> {
+        Err(Error::invalid(format!("cannot retrieve {} from a value that is neither map nor list", self.path[0])))
+    }
+
+    fn elements(self) -> Result<SeqExtractor<'p>> {
+        let index = parse_index(&self.path[0])?;
+        Ok(SeqExtractor::Element { index, rest: &self.path[1..], at: 0, found: None })
+    }
+
+    fn members(self) -> MapExtractor<'p> {
+        MapExtractor::Member { name: &self.path[0], rest: &self.path[1..], matched: false, found: None }
+    }
+
+    fn variant(self, name: &str) -> Result<Extractor<'p>> {
+        if self.path[0] != name {
+            return Err(absent(&self.path[0]));
+        }
+        Ok(Extractor { path: &self.path[1..] })
+    }
+}
+
+impl<'p> Serializer for Extractor<'p> {
+    type Ok = Value;
+    type Error = Error;
+
+    type SerializeSeq = SeqExtractor<'p>;
+    type SerializeTuple = SeqExtractor<'p>;
+    type SerializeTupleStruct = SeqExtractor<'p>;
+    type SerializeTupleVariant = SeqExtractor<'p>;
+    type SerializeMap = MapExtractor<'p>;
+    type SerializeStruct = MapExtractor<'p>;
+    type SerializeStructVariant = MapExtractor<'p>;
+
+    fn serialize_bool(self, _v: bool) -> Result<Value> {
+        self.scalar()
+    }
+
+    fn serialize_i8(self, _v: i8) -> Result<Value> {
+        self.scalar()
+    }
+
+    fn serialize_i16(self, _v: i16) -> Result<Value> {
+        self.scalar()
+    }
+
+    fn serialize_i32(self, _v: i32) -> Result<Value> {
+        self.scalar()
+    }
+
+    fn serialize_i64(self, _v: i64) -> Result<Value> {
+        self.scalar()
+    }
+
+    fn serialize_i128(self, _v: i128) -> Result<Value> {
+        self.scalar()
+    }
+
+    fn serialize_u8(self, _v: u8) -> Result<Value> {
+        self.scalar()
+    }
+
+    fn serialize_u16(self, _v: u16) -> Result<Value> {
+        self.scalar()
+    }
+
+    fn serialize_u32(self, _v: u32) -> Result<Value> {
+        self.scalar()
+    }
+
+    fn serialize_u64(self, _v: u64) -> Result<Value> {
+        self.scalar()
+    }
+
+    fn serialize_u128(self, _v: u128) -> Result<Value> {
+        self.scalar()
+    }
+
+    fn serialize_f32(self, _v: f32) -> Result<Value> {
+        self.scalar()
+    }
+
+    fn serialize_f64(self, _v: f64) -> Result<Value> {
+        self.scalar()
+    }
+
+    fn serialize_char(self, _v: char) -> Result<Value> {
+        self.scalar()
+    }
+
+    fn serialize_str(self, _v: &str) -> Result<Value> {
+        self.scalar()
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<Value> {
+        let index = parse_index(&self.path[0])?;
+        let byte = v.get(index).ok_or_else(|| Error::missing(format!("index {} is out of bounds", index)))?;
+        select(byte, &self.path[1..])
+    }
+
+    fn serialize_none(self) -> Result<Value> {
+        self.scalar()
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<Value> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Value> {
+        self.scalar()
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Value> {
+        self.scalar()
+    }
+
+    fn serialize_unit_variant(self, _name: &'static str, _index: u32, _variant: &'static str) -> Result<Value> {
+        self.scalar()
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(self, _name: &'static str, value: &T) -> Result<Value> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(self, _name: &'static str, _index: u32, name: &'static str, value: &T) -> Result<Value> {
+        select(value, self.variant(name)?.path)
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<SeqExtractor<'p>> {
+        self.elements()
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<SeqExtractor<'p>> {
+        self.elements()
+    }
+
+    fn serialize_tuple_struct(self, _name: &'static str, _len: usize) -> Result<SeqExtractor<'p>> {
+        self.elements()
+    }
+
+    fn serialize_tuple_variant(self, _name: &'static str, _index: u32, name: &'static str, len: usize) -> Result<SeqExtractor<'p>> {
+        let inner = self.variant(name)?;
+        if inner.path.is_empty() {
+            return Ok(SeqExtractor::Whole(ListSerializer { items: Vec::with_capacity(len), variant: None }));
+        }
+        inner.elements()
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<MapExtractor<'p>> {
+        Ok(self.members())
+    }
+
+    fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<MapExtractor<'p>> {
+        Ok(self.members())
+    }
+
+    fn serialize_struct_variant(self, _name: &'static str, _index: u32, name: &'static str, _len: usize) -> Result<MapExtractor<'p>> {
+        let inner = self.variant(name)?;
+        if inner.path.is_empty() {
+            return Ok(MapExtractor::Whole(MapSerializer { members: BTreeMap::new(), key: None, variant: None }));
+        }
+        Ok(inner.members())
+    }
+}
+
+/// Picks one element of a sequence, or collects all of them if the path ends at a tuple variant.
+enum SeqExtractor<'p> {
+    Element { index: usize, rest: &'p [String], at: usize, found: Option<Value> },
+    Whole(ListSerializer),
+}
+
+impl<'p> SeqExtractor<'p> {
+    fn push<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        match *self {
+            SeqExtractor::Element { index, rest, ref mut at, ref mut found } => {
+                if *at == index {
+                    *found = Some(select(value, rest)?);
+                }
+                *at += 1;
+                Ok(())
+            },
+            SeqExtractor::Whole(ref mut list) => list.push(value),
+        }
+    }
+
+    fn finish(self) -> Result<Value> {
+        match self {
+            SeqExtractor::Element { index, found, .. } => found.ok_or_else(|| Error::missing(format!("index {} is out of bounds", index))),
+            SeqExtractor::Whole(list) => list.finish(),
+        }
+    }
+}
+
+impl<'p> ser::SerializeSeq for SeqExtractor<'p> {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Value> {
+        self.finish()
+    }
+}
+
+impl<'p> ser::SerializeTuple for SeqExtractor<'p> {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Value> {
+        self.finish()
+    }
+}
+
+impl<'p> ser::SerializeTupleStruct for SeqExtractor<'p> {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Value> {
+        self.finish()
+    }
+}
+
+impl<'p> ser::SerializeTupleVariant for SeqExtractor<'p> {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Value> {
+        self.finish()
+    }
+}
+
+/// Picks one member of a map or struct, or collects all of them if the path ends at a struct variant.
+enum MapExtractor<'p> {
+    Member { name: &'p str, rest: &'p [String], matched: bool, found: Option<Value> },
+    Whole(MapSerializer),
+}
+
+impl<'p> MapExtractor<'p> {
+    fn field<T: Serialize + ?Sized>(&mut self, key: &'static str, value: &T) -> Result<()> {
+        match *self {
+            MapExtractor::Member { name, rest, ref mut found, .. } => {
+                if found.is_none() && key == name {
+                    *found = Some(select(value, rest)?);
+                }
+                Ok(())
+            },
+            MapExtractor::Whole(ref mut map) => map.insert(key.to_owned(), value),
+        }
+    }
+
+    fn finish(self) -> Result<Value> {
+        match self {
+            MapExtractor::Member { name, found, .. } => found.ok_or_else(|| absent(name)),
+            MapExtractor::Whole(map) => map.finish(),
+        }
+    }
+}
+
+impl<'p> ser::SerializeMap for MapExtractor<'p> {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<()> {
+        match *self {
+            MapExtractor::Member { name, ref mut matched, ref found, .. } => {
+                *matched = found.is_none() && self::key(key.serialize(ValueSerializer)?)? == name;
+                Ok(())
+            },
+            MapExtractor::Whole(ref mut map) => ser::SerializeMap::serialize_key(map, key),
+        }
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        match *self {
+            MapExtractor::Member { rest, ref mut matched, ref mut found, .. } => {
+                if *matched {
+                    *found = Some(select(value, rest)?);
+                    *matched = false;
+                }
+                Ok(())
+            },
+            MapExtractor::Whole(ref mut map) => ser::SerializeMap::serialize_value(map, value),
+        }
+    }
+
+    fn end(self) -> Result<Value> {
+        self.finish()
+    }
+}
+
+impl<'p> ser::SerializeStruct for MapExtractor<'p> {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, key: &'static str, value: &T) -> Result<()> {
+        self.field(key, value)
+    }
+
+    fn end(self) -> Result<Value> {
+        self.finish()
+    }
+}
+
+impl<'p> ser::SerializeStructVariant for MapExtractor<'p> {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, key: &'static str, value: &T) -> Result<()> {
+        self.field(key, value)
+    }
+
+    fn end(self) -> Result<Value> {
+        self.finish()
+    }
+}
+
+impl Serialize for Value {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        match *self {
+            Value::Null => serializer.serialize_unit(),
+            Value::Bool(b) => serializer.serialize_bool(b),
+            Value::I64(i) => serializer.serialize_i64(i),
+            Value::U64(u) => serializer.serialize_u64(u),
+            Value::F64(f) => serializer.serialize_f64(f),
+            Value::String(ref s) => serializer.serialize_str(s),
+            Value::List(ref items) => serializer.collect_seq(items),
+            Value::Map(ref members) => serializer.collect_map(members),
+        }
+    }
+}
+
+struct ValueVisitor;
+
+impl<'de> Visitor<'de> for ValueVisitor {
+    type Value = Value;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("any value")
+    }
+
+    fn visit_bool<E: de::Error>(self, v: bool) -> std::result::Result<Value, E> {
+        Ok(Value::Bool(v))
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> std::result::Result<Value, E> {
+        Ok(Value::I64(v))
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> std::result::Result<Value, E> {
+        Ok(Value::U64(v))
+    }
+
+    fn visit_f64<E: de::Error>(self, v: f64) -> std::result::Result<Value, E> {
+        Ok(Value::F64(v))
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> std::result::Result<Value, E> {
+        Ok(Value::String(v.to_owned()))
+    }
+
+    fn visit_string<E: de::Error>(self, v: String) -> std::result::Result<Value, E> {
+        Ok(Value::String(v))
+    }
+
+    fn visit_unit<E: de::Error>(self) -> std::result::Result<Value, E> {
+        Ok(Value::Null)
+    }
+
+    fn visit_none<E: de::Error>(self) -> std::result::Result<Value, E> {
+        Ok(Value::Null)
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> std::result::Result<Value, D::Error> {
+        Value::deserialize(deserializer)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> std::result::Result<Value, A::Error> {
+        let mut items = Vec::new();
+        while let Some(item) = seq.next_element()? {
+            items.push(item);
+        }
+        Ok(Value::List(items))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> std::result::Result<Value, A::Error> {
+        let mut members = BTreeMap::new();
+        while let Some((key, value)) = map.next_entry()? {
+            members.insert(key, value);
+        }
+        Ok(Value::Map(members))
+    }
+}
+
+impl<'de> Deserialize<'de> for Value {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Value, D::Error> {
+        deserializer.deserialize_any(ValueVisitor)
+    }
+}
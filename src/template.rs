@@ -0,0 +1,240 @@
+//! Text templates whose placeholders are resolved through a `Registry`.
+//!
+//! Placeholders are dotted paths of registered attribute names, blocks
+//! repeat their contents for every value a path leads to:
+//!
+//! ```
+//! # use attr::{Attr, IterableAttr, Registry};
+//! # use attr::template::Template;
+//! # struct User { data: Data, orders: Vec<Order> }
+//! # struct Data { name: String }
+//! # struct Order { title: String }
+//! # struct DataAttribute;
+//! # struct NameAttribute;
+//! # struct OrdersAttribute;
+//! # struct TitleAttribute;
+//! # impl<'a> Attr<&'a User> for DataAttribute { type Output = &'a Data; fn get(&self, u: &'a User) -> &'a Data { &u.data } fn name(&self) -> &str { "data" } }
+//! # impl<'a> Attr<&'a Data> for NameAttribute { type Output = &'a str; fn get(&self, d: &'a Data) -> &'a str { &d.name } fn name(&self) -> &str { "name" } }
+//! # impl<'a> Attr<&'a User> for OrdersAttribute { type Output = &'a [Order]; fn get(&self, u: &'a User) -> &'a [Order] { &u.orders } fn name(&self) -> &str { "orders" } }
+//! # impl<'a> IterableAttr<'a, &'a User> for OrdersAttribute { type Item = &'a Order; fn iter(&self, u: &'a User) -> Box<dyn Iterator<Item=&'a Order> + 'a> { Box::new(u.orders.iter()) } }
+//! # impl<'a> Attr<&'a Order> for TitleAttribute { type Output = &'a str; fn get(&self, o: &'a Order) -> &'a str { &o.title } fn name(&self) -> &str { "title" } }
+//! # fn main() -> attr::Result<()> {
+//! # let mut registry = Registry::new();
+//! # registry.register(DataAttribute).register(NameAttribute).register_iterable(OrdersAttribute).register(TitleAttribute);
+//! # let user = User { data: Data { name: "Flo".into() }, orders: vec![Order { title: "Socks".into() }] };
+//! let template = Template::parse("Hello {{data.name}},
+//! {{#each orders}}
+//! * {{title}}
+//! {{/each}}", &registry)?;
+//!
+//! let mail = template.render(&user)?;
+//! # assert!(mail.starts_with("Hello Flo,"));
+//! # assert!(mail.contains("* Socks"));
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Inside a block, paths start at the current value, which itself can be
+//! rendered through `{{this}}`. Values are rendered through `Display` if they
+//! are strings, booleans, characters or numbers, other values cannot be rendered.
+//! Output is not escaped.
+//!
+//! Attributes that always succeed are preferred over attributes that may
+//! fail, which are preferred over iterations, except for the last segment of
+//! a block path, where iterations are preferred.
+//!
+//! Errors name the line and column of the placeholder, like
+//! `line 1, column 7: data.name failed at data: data is not present`.
+
+use std::any::Any;
+use std::fmt;
+
+use super::{Registry, DynPath, AnyValue, PathDescriptor, SegmentKind, Error, ErrorKind, Result};
+
+/// The position of a tag in the template source.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Location {
+    line: usize,
+    column: usize,
+}
+
+impl Location {
+    fn of(source: &str, offset: usize) -> Location {
+        let before = &source[..offset];
+        let line = before.matches('\n').count() + 1;
+        let column = before.rsplit('\n').next().map_or(0, |l| l.chars().count()) + 1;
+        Location { line, column }
+    }
+
+    fn error<M: fmt::Display>(&self, kind: ErrorKind, message: M) -> Error {
+        Error::new(kind, format!("{}: {}", self, message))
+    }
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}, column {}", self.line, self.column)
+    }
+}
+
+struct Placeholder {
+    expression: String,
+    path: DynPath,
+    location: Location,
+}
+
+enum Node {
+    Text(String),
+    Value(Placeholder),
+    Each(Placeholder, Vec<Node>),
+}
+
+/// A parsed template, with all placeholders resolved to paths.
+pub struct Template {
+    nodes: Vec<Node>,
+}
+
+impl Template {
+    /// Parses `source`, resolving all placeholders through `registry`.
+    ///
+    /// Fails on malformed tags, unbalanced blocks and names that are not registered.
+    pub fn parse(source: &str, registry: &Registry) -> Result<Template> {
+        let mut stack: Vec<(Placeholder, Vec<Node>)> = Vec::new();
+        let mut nodes = Vec::new();
+        let mut at = 0;
+
+        while let Some(start) = source[at..].find("{{").map(|i| at + i) {
+            if start > at {
+                nodes.push(Node::Text(source[at..start].to_owned()));
+            }
+
+            let location = Location::of(source, start);
+            let end = source[start..].find("}}").map(|i| start + i)
+                .ok_or_else(|| location.error(ErrorKind::Invalid, "{{ is not closed"))?;
+            let tag = source[start + 2..end].trim();
+            at = end + 2;
+
+            if let Some(block) = tag.strip_prefix('#') {
+                let expression = match block.strip_prefix("each") {
+                    Some(rest) if rest.starts_with(char::is_whitespace) => rest.trim(),
+                    _ => return Err(location.error(ErrorKind::Invalid, format!("unknown block {{{{{}}}}}", tag))),
+                };
+                let placeholder = placeholder(expression, location, true, registry)?;
+                stack.push((placeholder, nodes));
+                nodes = Vec::new();
+            } else if let Some(block) = tag.strip_prefix('/') {
+                if block.trim() != "each" {
+                    return Err(location.error(ErrorKind::Invalid, format!("unknown block {{{{{}}}}}", tag)));
+                }
+                let (placeholder, outer) = stack.pop()
+                    .ok_or_else(|| location.error(ErrorKind::Invalid, "{{/each}} without {{#each}}"))?;
+                let body = std::mem::replace(&mut nodes, outer);
+                nodes.push(Node::Each(placeholder, body));
+            } else {
+                nodes.push(Node::Value(placeholder(tag, location, false, registry)?));
+            }
+        }
+
+        if let Some((placeholder, _)) = stack.pop() {
+            return Err(placeholder.location.error(ErrorKind::Invalid, format!("{{{{#each {}}}}} is not closed", placeholder.expression)));
+        }
+
+        if at < source.len() {
+            nodes.push(Node::Text(source[at..].to_owned()));
+        }
+
+        Ok(Template { nodes })
+    }
+
+    /// Renders the template for `value`.
+    pub fn render<T: Any>(&self, value: &T) -> Result<String> {
+        self.render_any(value)
+    }
+
+    /// Renders the template for a type-erased value.
+    pub fn render_any(&self, value: &dyn Any) -> Result<String> {
+        let mut out = String::new();
+        render_nodes(&self.nodes, value, &mut out)?;
+        Ok(out)
+    }
+}
+
+fn placeholder(expression: &str, location: Location, block: bool, registry: &Registry) -> Result<Placeholder> {
+    let mut descriptor = PathDescriptor::new();
+
+    if expression != "this" {
+        let names = expression.split('.').collect::<Vec<_>>();
+
+        for (i, name) in names.iter().enumerate() {
+            if name.is_empty() || name.contains(char::is_whitespace) {
+                return Err(location.error(ErrorKind::Invalid, format!("{} is not a valid path", expression)));
+            }
+
+            let kinds = registry.kinds(name);
+            let preferred: &[SegmentKind] = if block && i == names.len() - 1 {
+                &[SegmentKind::Iterable, SegmentKind::Attr, SegmentKind::Insecure]
+            } else {
+                &[SegmentKind::Attr, SegmentKind::Insecure, SegmentKind::Iterable]
+            };
+            let kind = preferred.iter().find(|k| kinds.contains(k))
+                .ok_or_else(|| location.error(ErrorKind::Invalid, format!("{} failed at {}: no attribute {} is registered", expression, name, name)))?;
+
+            descriptor = descriptor.segment(*name, *kind);
+        }
+    }
+
+    let path = registry.resolve(&descriptor).map_err(|e| location.error(e.kind(), e.message()))?;
+    Ok(Placeholder { expression: expression.to_owned(), path, location })
+}
+
+fn select<'a>(placeholder: &Placeholder, value: &'a dyn Any) -> Result<Vec<AnyValue<'a>>> {
+    placeholder.path.traverse_located(value).map_err(|(i, e)| {
+        let segment = placeholder.expression.split('.').nth(i).unwrap_or_default();
+        placeholder.location.error(e.kind(), format!("{} failed at {}: {}", placeholder.expression, segment, e.message()))
+    })
+}
+
+fn render_nodes(nodes: &[Node], value: &dyn Any, out: &mut String) -> Result<()> {
+    for node in nodes {
+        match *node {
+            Node::Text(ref text) => out.push_str(text),
+            Node::Value(ref placeholder) => {
+                let values = select(placeholder, value)?;
+                if values.len() != 1 {
+                    return Err(placeholder.location.error(ErrorKind::Invalid, format!("{} leads to {} values instead of one", placeholder.expression, values.len())));
+                }
+                let rendered = display(as_any(&values[0]))
+                    .ok_or_else(|| placeholder.location.error(ErrorKind::Invalid, format!("{} cannot be rendered", placeholder.expression)))?;
+                out.push_str(&rendered);
+            },
+            Node::Each(ref placeholder, ref body) => {
+                for item in select(placeholder, value)? {
+                    render_nodes(body, as_any(&item), out)?;
+                }
+            },
+        }
+    }
+    Ok(())
+}
+
+fn as_any<'v>(value: &'v AnyValue) -> &'v dyn Any {
+    match *value {
+        AnyValue::Borrowed(v) => v,
+        AnyValue::Owned(ref v) => &**v,
+    }
+}
+
+macro_rules! display_any {
+    ($value:expr, $($t:ty),*) => {
+        $(
+            if let Some(v) = $value.downcast_ref::<$t>() {
+                return Some(v.to_string());
+            }
+        )*
+    }
+}
+
+fn display(value: &dyn Any) -> Option<String> {
+    display_any!(value, String, &'static str, bool, char, i8, i16, i32, i64, isize, u8, u16, u32, u64, usize, f32, f64);
+    None
+}
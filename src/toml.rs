@@ -0,0 +1,65 @@
+//! Access to `toml::Value`s, available with the `toml` feature.
+//!
+//! `toml::Value` implements `DynValue`, so the `Key`, `Index` and `Each`
+//! attributes of `attr::value` apply to it: tables are maps, arrays are
+//! lists and datetimes are strings.
+//!
+//! ```
+//! # extern crate toml;
+//! # extern crate attr;
+//! # use attr::{retrieve_insecure, Traverse};
+//! # use attr::toml::Key;
+//! # fn main() -> attr::Result<()> {
+//! # let config: toml::Value = toml::from_str("[data]\nemail = \"flo@example.com\"").unwrap();
+//! let path = retrieve_insecure(Key::new("email")).try(Key::new("data"));
+//! let email = path.traverse(&config)?;
+//! # assert_eq!(email.as_str(), Some("flo@example.com"));
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Absent members and out of bounds indices fail with `ErrorKind::Missing`,
+//! values of the wrong shape with `ErrorKind::Invalid`.
+
+use toml_crate::Value;
+
+use super::DynValue;
+use super::value::Kind;
+
+pub use value::{Key, Index, Each};
+
+impl DynValue for Value {
+    fn kind(&self) -> Kind {
+        match *self {
+            Value::Boolean(_) => Kind::Bool,
+            Value::Integer(_) | Value::Float(_) => Kind::Number,
+            Value::String(_) | Value::Datetime(_) => Kind::String,
+            Value::Array(_) => Kind::List,
+            Value::Table(_) => Kind::Map,
+        }
+    }
+
+    fn get_key(&self, key: &str) -> Option<&Value> {
+        self.as_table().and_then(|t| t.get(key))
+    }
+
+    fn get_key_mut(&mut self, key: &str) -> Option<&mut Value> {
+        self.as_table_mut().and_then(|t| t.get_mut(key))
+    }
+
+    fn get_index(&self, index: usize) -> Option<&Value> {
+        self.as_array().and_then(|v| v.get(index))
+    }
+
+    fn get_index_mut(&mut self, index: usize) -> Option<&mut Value> {
+        self.as_array_mut().and_then(|v| v.get_mut(index))
+    }
+
+    fn values<'a>(&'a self) -> Box<dyn Iterator<Item=&'a Value> + 'a> {
+        match *self {
+            Value::Array(ref v) => Box::new(v.iter()),
+            Value::Table(ref t) => Box::new(t.values()),
+            _ => Box::new(std::iter::empty()),
+        }
+    }
+}
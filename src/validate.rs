@@ -0,0 +1,485 @@
+//! Validation of data along paths.
+//!
+//! A `Validator` checks a single value. Rules bind validators to paths,
+//! using `rule` for paths returning a single value and `each` for paths
+//! that iterate. Tuples of rules are rules themselves. Validators and
+//! rules are combined with `and` and `or` of `Combine`.
+//!
+//! Checking rules never stops at the first failure, but collects all of
+//! them in a `Report`, each with the path to the failing value.
+//!
+//! ```
+//! # use attr::{retrieve, Attr, IterableAttr};
+//! # use attr::validate::{rule, each, required, pattern, length, Check, Combine};
+//! # struct User { data: Data, items: Vec<Item> }
+//! # struct Data { email: String }
+//! # struct Item { name: String }
+//! # struct NameAttribute;
+//! # struct DataAttribute;
+//! # struct EmailAttribute;
+//! # struct ItemsAttribute;
+//! # impl<'a> Attr<&'a Item> for NameAttribute { type Output = &'a str; fn get(&self, i: &'a Item) -> &'a str { &i.name } fn name(&self) -> &str { "name" } }
+//! # impl<'a> Attr<&'a User> for DataAttribute { type Output = &'a Data; fn get(&self, u: &'a User) -> &'a Data { &u.data } fn name(&self) -> &str { "data" } }
+//! # impl<'a> Attr<&'a Data> for EmailAttribute { type Output = &'a str; fn get(&self, d: &'a Data) -> &'a str { &d.email } fn name(&self) -> &str { "email" } }
+//! # impl<'a> Attr<&'a User> for ItemsAttribute { type Output = &'a [Item]; fn get(&self, u: &'a User) -> &'a [Item] { &u.items } fn name(&self) -> &str { "items" } }
+//! # impl<'a> IterableAttr<'a, &'a User> for ItemsAttribute { type Item = &'a Item; fn iter(&self, u: &'a User) -> Box<dyn Iterator<Item=&'a Item> + 'a> { Box::new(u.items.iter()) } }
+//! # let user = User { data: Data { email: "flo@example.com".into() }, items: vec![Item { name: "".into() }] };
+//! let rules = (
+//!     rule(retrieve(EmailAttribute).from(DataAttribute), required().and(pattern("*@*"))),
+//!     each(retrieve(NameAttribute).mapped(ItemsAttribute), length(1, 20)),
+//! );
+//!
+//! let report = rules.validate(&user);
+//! # assert_eq!(report.failures().len(), 1);
+//! # assert_eq!(report.failures()[0].path, "items[0].name");
+//! ```
+
+use std::fmt;
+use std::marker::PhantomData;
+
+use super::{Traverse, PathInfo};
+
+/// The result of validating a single value. Failures carry a message.
+pub type Outcome = std::result::Result<(), String>;
+
+/// Validation of a single value.
+pub trait Validator<T> {
+    /// Validates a present value.
+    fn validate(&self, value: &T) -> Outcome;
+
+    /// Validates the absence of a value.
+    ///
+    /// Absent values are valid, unless the validator says otherwise, like `required` does.
+    fn validate_missing(&self) -> Outcome {
+        Ok(())
+    }
+}
+
+/// Combination of validators or rules.
+///
+/// Implemented by all validators and rules of this module. Custom
+/// validators can opt in with an empty implementation.
+pub trait Combine: Sized {
+    /// Combines two validators or rules, both need to succeed.
+    fn and<V>(self, other: V) -> And<Self, V> {
+        And { first: self, second: other }
+    }
+
+    /// Combines two validators or rules, one of them needs to succeed.
+    fn or<V>(self, other: V) -> Or<Self, V> {
+        Or { first: self, second: other }
+    }
+}
+
+/// Requires both validators or rules to succeed.
+pub struct And<V, W> {
+    first: V,
+    second: W,
+}
+
+/// Requires one of both validators or rules to succeed.
+pub struct Or<V, W> {
+    first: V,
+    second: W,
+}
+
+impl<T, V: Validator<T>, W: Validator<T>> Validator<T> for And<V, W> {
+    fn validate(&self, value: &T) -> Outcome {
+        self.first.validate(value).and_then(|_| self.second.validate(value))
+    }
+
+    fn validate_missing(&self) -> Outcome {
+        self.first.validate_missing().and_then(|_| self.second.validate_missing())
+    }
+}
+
+impl<T, V: Validator<T>, W: Validator<T>> Validator<T> for Or<V, W> {
+    fn validate(&self, value: &T) -> Outcome {
+        self.first.validate(value).or_else(|e| self.second.validate(value).map_err(|f| format!("{} or {}", e, f)))
+    }
+
+    fn validate_missing(&self) -> Outcome {
+        self.first.validate_missing().or_else(|e| self.second.validate_missing().map_err(|f| format!("{} or {}", e, f)))
+    }
+}
+
+/// Values that have a length, for the `length` validator.
+pub trait HasLength {
+    /// The length of the value. For strings, this is the number of characters.
+    fn length(&self) -> usize;
+}
+
+impl HasLength for str {
+    fn length(&self) -> usize {
+        self.chars().count()
+    }
+}
+
+impl HasLength for String {
+    fn length(&self) -> usize {
+        self.as_str().length()
+    }
+}
+
+impl<T> HasLength for [T] {
+    fn length(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<T> HasLength for Vec<T> {
+    fn length(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<T: HasLength + ?Sized> HasLength for &T {
+    fn length(&self) -> usize {
+        (**self).length()
+    }
+}
+
+/// Validator failing on absent values, see `required`.
+pub struct Required;
+
+/// Validator for the length of a value, see `length`.
+pub struct Length {
+    min: usize,
+    max: usize,
+}
+
+/// Validator for the range of a value, see `range`.
+pub struct Range<B> {
+    min: B,
+    max: B,
+}
+
+/// Validator matching strings against a pattern, see `pattern`.
+pub struct Pattern {
+    pattern: String,
+    chars: Vec<char>,
+}
+
+/// Validator for a set of allowed values, see `one_of`.
+pub struct OneOf<B> {
+    allowed: Vec<B>,
+}
+
+/// Validator backed by a function, see `custom`.
+pub struct Custom<F> {
+    f: F,
+}
+
+/// Requires the value to be present.
+pub fn required() -> Required {
+    Required
+}
+
+/// Requires the length of the value to be between `min` and `max`, inclusive.
+pub fn length(min: usize, max: usize) -> Length {
+    Length { min, max }
+}
+
+/// Requires the value to be between `min` and `max`, inclusive.
+pub fn range<B>(min: B, max: B) -> Range<B> {
+    Range { min, max }
+}
+
+/// Requires a string to match `pattern`, where `*` matches any
+/// number of characters and `?` matches exactly one.
+pub fn pattern<P: Into<String>>(pattern: P) -> Pattern {
+    let pattern = pattern.into();
+    let chars = pattern.chars().collect();
+    Pattern { pattern, chars }
+}
+
+/// Requires the value to be one of `allowed`.
+pub fn one_of<B>(allowed: Vec<B>) -> OneOf<B> {
+    OneOf { allowed }
+}
+
+/// Validates the value with a function.
+pub fn custom<T, F: Fn(&T) -> Outcome>(f: F) -> Custom<F> {
+    Custom { f }
+}
+
+impl<T> Validator<T> for Required {
+    fn validate(&self, _value: &T) -> Outcome {
+        Ok(())
+    }
+
+    fn validate_missing(&self) -> Outcome {
+        Err("is required".into())
+    }
+}
+
+impl<T: HasLength> Validator<T> for Length {
+    fn validate(&self, value: &T) -> Outcome {
+        let len = value.length();
+        if len < self.min || len > self.max {
+            Err(format!("length {} is not between {} and {}", len, self.min, self.max))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl<T: PartialOrd<B> + fmt::Debug, B: fmt::Debug> Validator<T> for Range<B> {
+    fn validate(&self, value: &T) -> Outcome {
+        if *value < self.min || *value > self.max {
+            Err(format!("{:?} is not between {:?} and {:?}", value, self.min, self.max))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Greedy wildcard matching, backtracking only to the most recent `*`.
+///
+/// Runs in `O(pattern.len() * value.len())` in the worst case.
+fn matches(pattern: &[char], value: &[char]) -> bool {
+    let (mut p, mut v) = (0, 0);
+    let mut star = None;
+
+    while v < value.len() {
+        match pattern.get(p) {
+            Some(&'*') => {
+                star = Some((p, v));
+                p += 1;
+            },
+            Some(&c) if c == '?' || c == value[v] => {
+                p += 1;
+                v += 1;
+            },
+            _ => match star {
+                Some((sp, sv)) => {
+                    star = Some((sp, sv + 1));
+                    p = sp + 1;
+                    v = sv + 1;
+                },
+                None => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+impl<T: AsRef<str>> Validator<T> for Pattern {
+    fn validate(&self, value: &T) -> Outcome {
+        let value = value.as_ref().chars().collect::<Vec<_>>();
+        if matches(&self.chars, &value) {
+            Ok(())
+        } else {
+            Err(format!("does not match {}", self.pattern))
+        }
+    }
+}
+
+impl<T: PartialEq<B> + fmt::Debug, B: fmt::Debug> Validator<T> for OneOf<B> {
+    fn validate(&self, value: &T) -> Outcome {
+        if self.allowed.iter().any(|a| *value == *a) {
+            Ok(())
+        } else {
+            Err(format!("{:?} is not one of {:?}", value, self.allowed))
+        }
+    }
+}
+
+impl<T, F: Fn(&T) -> Outcome> Validator<T> for Custom<F> {
+    fn validate(&self, value: &T) -> Outcome {
+        (self.f)(value)
+    }
+}
+
+impl Combine for Required {}
+impl Combine for Length {}
+impl Combine for Pattern {}
+impl<B> Combine for Range<B> {}
+impl<B> Combine for OneOf<B> {}
+impl<F> Combine for Custom<F> {}
+impl<V, W> Combine for And<V, W> {}
+impl<V, W> Combine for Or<V, W> {}
+impl<P, Y, V> Combine for PathRule<P, Y, V> {}
+impl<P, Y, V> Combine for EachRule<P, Y, V> {}
+
+/// A single failed validation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Failure {
+    /// The path to the failing value, like `items[2].name`
+    pub path: String,
+    /// Why the value is invalid
+    pub message: String,
+}
+
+/// All failures found when checking rules.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Report {
+    failures: Vec<Failure>,
+}
+
+impl Report {
+    /// Creates an empty report.
+    pub fn new() -> Report {
+        Report::default()
+    }
+
+    /// Records all failures of `other`.
+    pub fn merge(&mut self, other: Report) {
+        self.failures.extend(other.failures);
+    }
+
+    /// Records a failure.
+    pub fn fail<P: Into<String>, M: Into<String>>(&mut self, path: P, message: M) {
+        self.failures.push(Failure { path: path.into(), message: message.into() });
+    }
+
+    /// Whether no failures were found
+    pub fn is_valid(&self) -> bool {
+        self.failures.is_empty()
+    }
+
+    /// All failures, in order of checking
+    pub fn failures(&self) -> &[Failure] {
+        &self.failures
+    }
+
+    /// Turns the report into a `Result`, failing if there are failures.
+    pub fn into_result(self) -> std::result::Result<(), Report> {
+        if self.is_valid() { Ok(()) } else { Err(self) }
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, failure) in self.failures.iter().enumerate() {
+            if i > 0 {
+                f.write_str("\n")?;
+            }
+            write!(f, "{}: {}", failure.path, failure.message)?;
+        }
+        Ok(())
+    }
+}
+
+/// A set of validations to run on a value.
+///
+/// Implemented by the rules built through `rule` and `each`, and by tuples of rules.
+pub trait Check<'a, 'b: 'a, X: 'b> {
+    /// Runs the validations, adding all failures to `report`.
+    fn check(&'a self, obj: X, report: &mut Report);
+
+    /// Runs the validations and returns a report.
+    fn validate(&'a self, obj: X) -> Report {
+        let mut report = Report::new();
+        self.check(obj, &mut report);
+        report
+    }
+}
+
+/// A validator bound to a path, see `rule`.
+pub struct PathRule<P, Y, V> {
+    path: P,
+    validator: V,
+    phantom_y: PhantomData<Y>,
+}
+
+/// A validator bound to every item of an iterating path, see `each`.
+pub struct EachRule<P, Y, V> {
+    path: P,
+    validator: V,
+    phantom_y: PhantomData<Y>,
+}
+
+/// Validates the value returned by `path`.
+pub fn rule<P, Y, V>(path: P, validator: V) -> PathRule<P, Y, V> {
+    PathRule { path, validator, phantom_y: PhantomData }
+}
+
+/// Validates every value returned by an iterating `path`, such as a `MapPath`.
+pub fn each<P, Y, V>(path: P, validator: V) -> EachRule<P, Y, V> {
+    EachRule { path, validator, phantom_y: PhantomData }
+}
+
+impl<'a, 'b: 'a, X: 'b, Y: 'b, P: Traverse<'a, 'b, X, Y> + PathInfo, V: Validator<Y>> Check<'a, 'b, X> for PathRule<P, Y, V> {
+    fn check(&'a self, obj: X, report: &mut Report) {
+        let outcome = match self.path.traverse(obj) {
+            Ok(value) => self.validator.validate(&value),
+            Err(ref e) if e.is_missing() => self.validator.validate_missing(),
+            Err(e) => Err(e.to_string()),
+        };
+
+        if let Err(message) = outcome {
+            report.fail(self.path.breadcrumb(None), message);
+        }
+    }
+}
+
+impl<'a, X: 'a, Y: 'a, P, V: Validator<Y>> Check<'a, 'a, X> for EachRule<P, Y, V>
+    where P: Traverse<'a, 'a, X, Box<dyn Iterator<Item=super::Result<Y>> + 'a>> + PathInfo
+{
+    fn check(&'a self, obj: X, report: &mut Report) {
+        let iter = match self.path.traverse(obj) {
+            Ok(iter) => iter,
+            Err(e) => return report.fail(self.path.breadcrumb(None), e.to_string()),
+        };
+
+        for (i, item) in iter.enumerate() {
+            let outcome = match item {
+                Ok(value) => self.validator.validate(&value),
+                Err(ref e) if e.is_missing() => self.validator.validate_missing(),
+                Err(e) => Err(e.to_string()),
+            };
+
+            if let Err(message) = outcome {
+                report.fail(self.path.breadcrumb(Some(i)), message);
+            }
+        }
+    }
+}
+
+/// Checks both rules and reports the failures of both.
+impl<'a, 'b: 'a, X: 'b + Copy, C: Check<'a, 'b, X>, D: Check<'a, 'b, X>> Check<'a, 'b, X> for And<C, D> {
+    fn check(&'a self, obj: X, report: &mut Report) {
+        self.first.check(obj, report);
+        self.second.check(obj, report);
+    }
+}
+
+/// Checks the second rule only if the first one fails, and reports
+/// the failures of both if both fail.
+impl<'a, 'b: 'a, X: 'b + Copy, C: Check<'a, 'b, X>, D: Check<'a, 'b, X>> Check<'a, 'b, X> for Or<C, D> {
+    fn check(&'a self, obj: X, report: &mut Report) {
+        let first = self.first.validate(obj);
+        if first.is_valid() {
+            return;
+        }
+
+        let second = self.second.validate(obj);
+        if !second.is_valid() {
+            report.merge(first);
+            report.merge(second);
+        }
+    }
+}
+
+macro_rules! check_tuple {
+    ($($C:ident $idx:tt),+) => {
+        impl<'a, 'b: 'a, X: 'b + Copy, $($C: Check<'a, 'b, X>),+> Check<'a, 'b, X> for ($($C,)+) {
+            fn check(&'a self, obj: X, report: &mut Report) {
+                $(self.$idx.check(obj, report);)+
+            }
+        }
+
+        impl<$($C),+> Combine for ($($C,)+) {}
+    }
+}
+
+check_tuple!(C0 0);
+check_tuple!(C0 0, C1 1);
+check_tuple!(C0 0, C1 1, C2 2);
+check_tuple!(C0 0, C1 1, C2 2, C3 3);
+check_tuple!(C0 0, C1 1, C2 2, C3 3, C4 4);
+check_tuple!(C0 0, C1 1, C2 2, C3 3, C4 4, C5 5);
+check_tuple!(C0 0, C1 1, C2 2, C3 3, C4 4, C5 5, C6 6);
+check_tuple!(C0 0, C1 1, C2 2, C3 3, C4 4, C5 5, C6 6, C7 7);
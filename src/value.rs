@@ -0,0 +1,321 @@
+//! Format-agnostic access to dynamic values.
+//!
+//! `DynValue` abstracts over value trees like `serde_json::Value`: values
+//! are maps, lists or scalars. `Key`, `Index` and `Each` are attributes
+//! implemented once for all `DynValue`s, so a new format only needs to
+//! implement the trait.
+//!
+//! ```
+//! # use std::collections::BTreeMap;
+//! # use attr::{retrieve_insecure, Traverse, Value};
+//! # use attr::value::Key;
+//! # fn main() -> attr::Result<()> {
+//! # let mut data = BTreeMap::new();
+//! # data.insert("email".to_string(), Value::from("flo@example.com"));
+//! # let mut user = BTreeMap::new();
+//! # user.insert("data".to_string(), Value::Map(data));
+//! # let value = Value::Map(user);
+//! let path = retrieve_insecure(Key::new("email")).try(Key::new("data"));
+//! let email = path.traverse(&value)?;
+//! # assert_eq!(email, &Value::from("flo@example.com"));
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! The same path traverses a `serde_json::Value` or `toml::Value` with
+//! the respective feature enabled.
+//!
+//! Absent members and out of bounds indices fail with `ErrorKind::Missing`,
+//! values of the wrong shape with `ErrorKind::Invalid`.
+
+use std::collections::BTreeMap;
+
+#[cfg(feature = "serde")]
+pub use serialize::{to_value, Field};
+
+use super::{Attr, InsecureAttr, InsecureIndexableAttr, IterableAttr, InsecureIterableAttr, Error, Result, parse_index};
+
+/// The shape of a dynamic value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Kind {
+    /// The absence of a value
+    Null,
+    /// `true` or `false`
+    Bool,
+    /// Any number
+    Number,
+    /// A string
+    String,
+    /// A sequence of values, accessed by index
+    List,
+    /// Values accessed by name
+    Map,
+}
+
+/// A value of a dynamically typed format.
+pub trait DynValue {
+    /// The shape of the value
+    fn kind(&self) -> Kind;
+
+    /// The member named `key`, if this is a map
+    fn get_key(&self, key: &str) -> Option<&Self>;
+
+    /// The member named `key` for modification, if this is a map
+    fn get_key_mut(&mut self, key: &str) -> Option<&mut Self>;
+
+    /// The element at `index`, if this is a list
+    fn get_index(&self, index: usize) -> Option<&Self>;
+
+    /// The element at `index` for modification, if this is a list
+    fn get_index_mut(&mut self, index: usize) -> Option<&mut Self>;
+
+    /// The elements of a list or the members of a map, nothing for other values
+    fn values<'a>(&'a self) -> Box<dyn Iterator<Item=&'a Self> + 'a>;
+}
+
+/// Retrieves a member of a map by name, or an element of a list by index.
+pub struct Key<'k> {
+    name: &'k str,
+}
+
+impl<'k> Key<'k> {
+    /// Creates an attribute retrieving `name`.
+    pub fn new(name: &'k str) -> Key<'k> {
+        Key { name }
+    }
+}
+
+fn element<T>(element: Option<T>, index: usize) -> Result<T> {
+    element.ok_or_else(|| Error::missing(format!("index {} is out of bounds", index)))
+}
+
+impl<'a, 'k, V: DynValue> InsecureAttr<&'a V> for Key<'k> {
+    type Output = &'a V;
+
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn get(&self, i: &'a V) -> Result<&'a V> {
+        match i.kind() {
+            Kind::Map => i.get_key(self.name).ok_or_else(|| Error::missing(format!("{} is not present", self.name))),
+            Kind::List => {
+                let index = parse_index(self.name)?;
+                element(i.get_index(index), index)
+            },
+            _ => Err(Error::invalid(format!("cannot retrieve {} from a value that is neither map nor list", self.name))),
+        }
+    }
+}
+
+impl<'a, 'k, V: DynValue> InsecureAttr<&'a mut V> for Key<'k> {
+    type Output = &'a mut V;
+
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn get(&self, i: &'a mut V) -> Result<&'a mut V> {
+        match i.kind() {
+            Kind::Map => i.get_key_mut(self.name).ok_or_else(|| Error::missing(format!("{} is not present", self.name))),
+            Kind::List => {
+                let index = parse_index(self.name)?;
+                element(i.get_index_mut(index), index)
+            },
+            _ => Err(Error::invalid(format!("cannot retrieve {} from a value that is neither map nor list", self.name))),
+        }
+    }
+}
+
+/// Retrieves an element of the list held by the member.
+impl<'a, 'k, V: DynValue> InsecureIndexableAttr<&'a V, usize> for Key<'k> {
+    type Output = &'a V;
+
+    fn at(&self, i: &'a V, idx: usize) -> Result<&'a V> {
+        Index::new(idx).get(self.get(i)?)
+            .map_err(|e| if e.is_missing() { e } else { Error::invalid(format!("{} is not a list", self.name)) })
+    }
+}
+
+/// Retrieves an element of the list held by the member.
+impl<'a, 'k, V: DynValue> InsecureIndexableAttr<&'a mut V, usize> for Key<'k> {
+    type Output = &'a mut V;
+
+    fn at(&self, i: &'a mut V, idx: usize) -> Result<&'a mut V> {
+        let name = self.name;
+        Index::new(idx).get(self.get(i)?)
+            .map_err(|e| if e.is_missing() { e } else { Error::invalid(format!("{} is not a list", name)) })
+    }
+}
+
+/// Retrieves an element of a list.
+pub struct Index {
+    index: usize,
+    name: String,
+}
+
+impl Index {
+    /// Creates an attribute retrieving the element at `index`.
+    pub fn new(index: usize) -> Index {
+        Index { index, name: index.to_string() }
+    }
+}
+
+impl<'a, V: DynValue> InsecureAttr<&'a V> for Index {
+    type Output = &'a V;
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn get(&self, i: &'a V) -> Result<&'a V> {
+        match i.kind() {
+            Kind::List => element(i.get_index(self.index), self.index),
+            _ => Err(Error::invalid(format!("cannot retrieve index {} from a value that is not a list", self.index))),
+        }
+    }
+}
+
+impl<'a, V: DynValue> InsecureAttr<&'a mut V> for Index {
+    type Output = &'a mut V;
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn get(&self, i: &'a mut V) -> Result<&'a mut V> {
+        match i.kind() {
+            Kind::List => element(i.get_index_mut(self.index), self.index),
+            _ => Err(Error::invalid(format!("cannot retrieve index {} from a value that is not a list", self.index))),
+        }
+    }
+}
+
+/// Iterates over the elements of a list or the members of a map.
+///
+/// As an `IterableAttr`, other values have no items. As an `InsecureIterableAttr`,
+/// iterating over them fails. As an `Attr`, it returns the value itself.
+pub struct Each;
+
+impl<'a, V: DynValue> Attr<&'a V> for Each {
+    type Output = &'a V;
+
+    fn name(&self) -> &str {
+        "*"
+    }
+
+    fn get(&self, i: &'a V) -> &'a V {
+        i
+    }
+}
+
+impl<'a, V: DynValue> IterableAttr<'a, &'a V> for Each {
+    type Item = &'a V;
+
+    fn iter(&self, i: &'a V) -> Box<dyn Iterator<Item=&'a V> + 'a> {
+        i.values()
+    }
+}
+
+impl<'a, V: DynValue> InsecureIterableAttr<'a, &'a V> for Each {
+    type Item = &'a V;
+
+    fn iter(&self, i: &'a V) -> Result<Box<dyn Iterator<Item=&'a V> + 'a>> {
+        match i.kind() {
+            Kind::List | Kind::Map => Ok(i.values()),
+            _ => Err(Error::invalid("cannot iterate over a value that is neither map nor list")),
+        }
+    }
+}
+
+/// An owned dynamic value.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum Value {
+    /// The absence of a value
+    #[default]
+    Null,
+    /// `true` or `false`
+    Bool(bool),
+    /// A signed integer
+    I64(i64),
+    /// An unsigned integer
+    U64(u64),
+    /// A floating point number
+    F64(f64),
+    /// A string
+    String(String),
+    /// A sequence of values
+    List(Vec<Value>),
+    /// Values by name
+    Map(BTreeMap<String, Value>),
+}
+
+impl DynValue for Value {
+    fn kind(&self) -> Kind {
+        match *self {
+            Value::Null => Kind::Null,
+            Value::Bool(_) => Kind::Bool,
+            Value::I64(_) | Value::U64(_) | Value::F64(_) => Kind::Number,
+            Value::String(_) => Kind::String,
+            Value::List(_) => Kind::List,
+            Value::Map(_) => Kind::Map,
+        }
+    }
+
+    fn get_key(&self, key: &str) -> Option<&Value> {
+        match *self {
+            Value::Map(ref m) => m.get(key),
+            _ => None,
+        }
+    }
+
+    fn get_key_mut(&mut self, key: &str) -> Option<&mut Value> {
+        match *self {
+            Value::Map(ref mut m) => m.get_mut(key),
+            _ => None,
+        }
+    }
+
+    fn get_index(&self, index: usize) -> Option<&Value> {
+        match *self {
+            Value::List(ref v) => v.get(index),
+            _ => None,
+        }
+    }
+
+    fn get_index_mut(&mut self, index: usize) -> Option<&mut Value> {
+        match *self {
+            Value::List(ref mut v) => v.get_mut(index),
+            _ => None,
+        }
+    }
+
+    fn values<'a>(&'a self) -> Box<dyn Iterator<Item=&'a Value> + 'a> {
+        match *self {
+            Value::List(ref v) => Box::new(v.iter()),
+            Value::Map(ref m) => Box::new(m.values()),
+            _ => Box::new(std::iter::empty()),
+        }
+    }
+}
+
+macro_rules! value_from {
+    ($($t:ty => $variant:ident),*) => {
+        $(
+            impl From<$t> for Value {
+                fn from(v: $t) -> Value {
+                    Value::$variant(v.into())
+                }
+            }
+        )*
+    }
+}
+
+value_from!(bool => Bool, i8 => I64, i16 => I64, i32 => I64, i64 => I64, u8 => U64, u16 => U64, u32 => U64, u64 => U64,
+            f32 => F64, f64 => F64, String => String, Vec<Value> => List, BTreeMap<String, Value> => Map);
+
+impl<'a> From<&'a str> for Value {
+    fn from(v: &'a str) -> Value {
+        Value::String(v.to_owned())
+    }
+}
@@ -0,0 +1,120 @@
+use std::fmt;
+
+use super::{Traverse, PathInfo, Segment, Result};
+
+/// A path that traverses several paths on the same input and
+/// returns all results as a tuple.
+///
+/// As the input is handed to all paths, it needs to be `Copy`,
+/// which is the case for shared references.
+///
+/// Paths are usually inferred and should not be directly used
+/// in user code.
+pub struct ZipPath<Paths> {
+    paths: Paths,
+}
+
+/// `zip` combines a tuple of 1 to 8 paths working on the same input
+/// into a path returning a tuple of their results.
+///
+/// The traversal fails with the first failing path.
+pub fn zip<Paths>(paths: Paths) -> ZipPath<Paths> {
+    ZipPath { paths }
+}
+
+macro_rules! zip_traverse {
+    ($($P:ident $Y:ident $idx:tt),+) => {
+        impl<'a, 'b: 'a, X: 'b + Copy, $($Y: 'b, $P: Traverse<'a, 'b, X, $Y>),+> Traverse<'a, 'b, X, ($($Y,)+)> for ZipPath<($($P,)+)> {
+            #[inline]
+            fn traverse(&'a self, obj: X) -> Result<($($Y,)+)> {
+                Ok(($(self.paths.$idx.traverse(obj)?,)+))
+            }
+        }
+    }
+}
+
+/// Zipped paths are described by the segments all of them share, as the
+/// values they lead to are all at or below these segments. `Display`
+/// renders all paths, like `(data.email, data.name)`.
+macro_rules! zip_info {
+    ($($P:ident $idx:tt),+) => {
+        impl<$($P: PathInfo),+> PathInfo for ZipPath<($($P,)+)> {
+            fn push_segments<'a>(&'a self, segments: &mut Vec<Segment<'a>>) {
+                let all = [$(self.paths.$idx.segments()),+];
+                let (first, rest) = all.split_first().expect("zipped paths are never empty");
+                let shared = first.iter().enumerate()
+                    .take_while(|&(i, s)| rest.iter().all(|other| other.get(i) == Some(s)))
+                    .count();
+                segments.extend_from_slice(&first[..shared]);
+            }
+        }
+
+        impl<$($P: PathInfo),+> fmt::Display for ZipPath<($($P,)+)> {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                let rendered = [$(self.paths.$idx.breadcrumb(None)),+];
+                write!(f, "({})", rendered.join(", "))
+            }
+        }
+    }
+}
+
+zip_info!(P0 0);
+zip_info!(P0 0, P1 1);
+zip_info!(P0 0, P1 1, P2 2);
+zip_info!(P0 0, P1 1, P2 2, P3 3);
+zip_info!(P0 0, P1 1, P2 2, P3 3, P4 4);
+zip_info!(P0 0, P1 1, P2 2, P3 3, P4 4, P5 5);
+zip_info!(P0 0, P1 1, P2 2, P3 3, P4 4, P5 5, P6 6);
+zip_info!(P0 0, P1 1, P2 2, P3 3, P4 4, P5 5, P6 6, P7 7);
+
+zip_traverse!(P0 Y0 0);
+zip_traverse!(P0 Y0 0, P1 Y1 1);
+zip_traverse!(P0 Y0 0, P1 Y1 1, P2 Y2 2);
+zip_traverse!(P0 Y0 0, P1 Y1 1, P2 Y2 2, P3 Y3 3);
+zip_traverse!(P0 Y0 0, P1 Y1 1, P2 Y2 2, P3 Y3 3, P4 Y4 4);
+zip_traverse!(P0 Y0 0, P1 Y1 1, P2 Y2 2, P3 Y3 3, P4 Y4 4, P5 Y5 5);
+zip_traverse!(P0 Y0 0, P1 Y1 1, P2 Y2 2, P3 Y3 3, P4 Y4 4, P5 Y5 5, P6 Y6 6);
+zip_traverse!(P0 Y0 0, P1 Y1 1, P2 Y2 2, P3 Y3 3, P4 Y4 4, P5 Y5 5, P6 Y6 6, P7 Y7 7);
+
+/// Fills a struct from a list of paths traversed on the same input.
+///
+/// Evaluates to a `Result` of the struct, failing with the first
+/// failing path. The struct may be named with a module path and type
+/// arguments, like `dto::Named<&str>`. Field expressions are evaluated
+/// in place, so they may use `?` and `return`.
+///
+/// ```
+/// # #[macro_use] extern crate attr;
+/// # use attr::{retrieve, Attr};
+/// # struct User { name: String, data: Data }
+/// # struct Data { email: String }
+/// # struct NameAttribute;
+/// # struct DataAttribute;
+/// # struct EmailAttribute;
+/// # impl<'a> Attr<&'a User> for NameAttribute { type Output = &'a str; fn get(&self, u: &'a User) -> &'a str { &u.name } fn name(&self) -> &str { "name" } }
+/// # impl<'a> Attr<&'a User> for DataAttribute { type Output = &'a Data; fn get(&self, u: &'a User) -> &'a Data { &u.data } fn name(&self) -> &str { "data" } }
+/// # impl<'a> Attr<&'a Data> for EmailAttribute { type Output = &'a str; fn get(&self, d: &'a Data) -> &'a str { &d.email } fn name(&self) -> &str { "email" } }
+/// struct UserDto<'a> {
+///     name: &'a str,
+///     email: &'a str,
+/// }
+///
+/// # fn main() -> attr::Result<()> {
+/// # let user = User { name: "Flo".into(), data: Data { email: "flo@example.com".into() } };
+/// let dto = project!(&user => UserDto {
+///     name: retrieve(NameAttribute),
+///     email: retrieve(EmailAttribute).from(DataAttribute),
+/// })?;
+/// # assert_eq!((dto.name, dto.email), ("Flo", "flo@example.com"));
+/// # Ok(())
+/// # }
+/// ```
+#[macro_export]
+macro_rules! project {
+    ($obj:expr => $($ty:ident)::+ $(<$($generic:ty),+>)? { $($field:ident : $path:expr),+ $(,)? }) => {
+        match $crate::Traverse::traverse(&$crate::zip(($($path,)+)), $obj) {
+            ::std::result::Result::Ok(($($field,)+)) => ::std::result::Result::Ok($($ty)::+ $(::<$($generic),+>)? { $($field),+ }),
+            ::std::result::Result::Err(e) => ::std::result::Result::Err::<_, $crate::Error>(e),
+        }
+    }
+}
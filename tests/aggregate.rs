@@ -0,0 +1,129 @@
+extern crate attr;
+
+use attr::retrieve;
+use attr::retrieve_insecure;
+use attr::Aggregate;
+
+pub struct Department {
+    employees: Vec<Employee>,
+}
+
+pub struct Employee {
+    name: String,
+    salary: Option<u32>,
+}
+
+pub mod department {
+    use attr::Attr;
+    use attr::IterableAttr;
+
+    use super::Department;
+    use super::Employee;
+
+    pub struct Employees;
+
+    impl<'a> Attr<&'a Department> for Employees {
+        type Output = &'a [Employee];
+
+        fn get(&self, i: &'a Department) -> &'a [Employee] {
+            i.employees.as_ref()
+        }
+
+        fn name(&self) -> &'static str {
+            "employees"
+        }
+    }
+
+    impl<'a> IterableAttr<'a, &'a Department> for Employees {
+        type Item = &'a Employee;
+
+        fn iter(&self, i: &'a Department) -> Box<dyn Iterator<Item=&'a Employee> + 'a> {
+            Box::new(self.get(i).iter())
+        }
+    }
+}
+
+pub mod employee {
+    use attr::Attr;
+    use attr::InsecureAttr;
+    use attr::Error;
+    use attr::Result;
+
+    use super::Employee;
+
+    pub struct Name;
+    pub struct Salary;
+
+    impl<'a> Attr<&'a Employee> for Name {
+        type Output = &'a str;
+
+        fn get(&self, i: &'a Employee) -> &'a str {
+            i.name.as_ref()
+        }
+
+        fn name(&self) -> &'static str {
+            "name"
+        }
+    }
+
+    impl<'a> InsecureAttr<&'a Employee> for Salary {
+        type Output = u32;
+
+        fn get(&self, i: &'a Employee) -> Result<u32> {
+            i.salary.ok_or_else(|| Error::missing(format!("{} has no salary", i.name)))
+        }
+
+        fn name(&self) -> &'static str {
+            "salary"
+        }
+    }
+}
+
+fn employee(name: &str, salary: Option<u32>) -> Employee {
+    Employee { name: name.into(), salary }
+}
+
+fn department() -> Department {
+    Department { employees: vec![employee("ada", Some(300)), employee("bob", Some(100)), employee("cyd", Some(200))] }
+}
+
+#[test]
+fn aggregate_values() {
+    let dept = department();
+    let salaries = retrieve_insecure(employee::Salary).mapped(department::Employees);
+
+    assert_eq!(salaries.count(&dept), Ok(3));
+    assert_eq!(salaries.sum::<u32>(&dept), Ok(600));
+    assert_eq!(salaries.min(&dept), Ok(Some(100)));
+    assert_eq!(salaries.max(&dept), Ok(Some(300)));
+    assert_eq!(salaries.first(&dept), Ok(Some(300)));
+    assert_eq!(salaries.collect_vec(&dept), Ok(vec![300, 100, 200]));
+    assert_eq!(salaries.fold(&dept, 0, |acc, s| acc.max(s / 100)), Ok(3));
+    assert_eq!(salaries.any(&dept, |s| s > 250), Ok(true));
+    assert_eq!(salaries.all(&dept, |s| s > 150), Ok(false));
+}
+
+#[test]
+fn aggregate_empty() {
+    let dept = Department { employees: vec![] };
+    let names = retrieve(employee::Name).mapped(department::Employees);
+
+    assert_eq!(names.count(&dept), Ok(0));
+    assert_eq!(names.max(&dept), Ok(None));
+    assert_eq!(names.first(&dept), Ok(None));
+    assert_eq!(names.all(&dept, |n| n.is_empty()), Ok(true));
+}
+
+#[test]
+fn aggregate_failure_index() {
+    let mut dept = department();
+    dept.employees.insert(1, employee("eve", None));
+    let salaries = retrieve_insecure(employee::Salary).mapped(department::Employees);
+
+    let error = salaries.sum::<u32>(&dept).unwrap_err();
+    assert_eq!(error.index(), Some(1));
+    assert_eq!(error.to_string(), "at index 1: eve has no salary");
+
+    assert_eq!(salaries.any(&dept, |s| s > 250), Ok(true));
+    assert_eq!(salaries.count(&dept).unwrap_err().index(), Some(1));
+}
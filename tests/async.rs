@@ -0,0 +1,233 @@
+#![cfg(feature = "async")]
+
+extern crate attr;
+extern crate futures;
+
+use std::collections::HashMap;
+
+use futures::executor::block_on;
+use futures::StreamExt;
+
+use attr::retrieve;
+use attr::retrieve_async;
+use attr::AsyncTraverse;
+use attr::ErrorKind;
+use attr::PathInfo;
+
+use store::*;
+
+pub struct Order {
+    customer: u32,
+    items: Vec<u32>,
+}
+
+pub struct Shipment {
+    order: Order,
+}
+
+pub struct Customer {
+    email: String,
+}
+
+pub struct Product {
+    price: u32,
+}
+
+/// An in-memory stand-in for a remote store. Every lookup is
+/// pending once before it resolves.
+#[derive(Default)]
+pub struct Store {
+    customers: HashMap<u32, Customer>,
+    products: HashMap<u32, Product>,
+}
+
+pub mod store {
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::task::{Context, Poll};
+
+    use attr::{Attr, IterableAttr, AsyncAttr, AsyncInsecureAttr, Error, Result};
+    use attr::asynchronous::BoxFuture;
+
+    use super::{Shipment, Order, Customer, Product, Store};
+
+    struct Delayed<T> {
+        value: Option<T>,
+        pending: bool,
+    }
+
+    impl<T: Unpin> Future for Delayed<T> {
+        type Output = T;
+
+        fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<T> {
+            let this = self.get_mut();
+            if this.pending {
+                this.pending = false;
+                cx.waker().wake_by_ref();
+                return Poll::Pending;
+            }
+            Poll::Ready(this.value.take().expect("polled after completion"))
+        }
+    }
+
+    fn delayed<'a, T: Unpin + 'a>(value: T) -> BoxFuture<'a, T> {
+        Box::pin(Delayed { value: Some(value), pending: true })
+    }
+
+    pub struct CustomerOf<'s>(pub &'s Store);
+    pub struct ProductOf<'s>(pub &'s Store);
+    pub struct Total;
+    pub struct Email;
+    pub struct Price;
+    pub struct Items;
+    pub struct OrderField;
+
+    impl<'a, 'o, 's> AsyncInsecureAttr<'a, &'o Order> for CustomerOf<'s> {
+        type Output = &'s Customer;
+
+        fn name(&self) -> &str {
+            "customer"
+        }
+
+        fn get(&'a self, order: &'o Order) -> BoxFuture<'a, Result<&'s Customer>> {
+            delayed(self.0.customers.get(&order.customer).ok_or_else(|| Error::missing(format!("customer {} not found", order.customer))))
+        }
+    }
+
+    impl<'a, 'i, 's> AsyncInsecureAttr<'a, &'i u32> for ProductOf<'s> {
+        type Output = &'s Product;
+
+        fn name(&self) -> &str {
+            "product"
+        }
+
+        fn get(&'a self, id: &'i u32) -> BoxFuture<'a, Result<&'s Product>> {
+            delayed(self.0.products.get(id).ok_or_else(|| Error::missing(format!("product {} not found", id))))
+        }
+    }
+
+    impl<'a, 'o> AsyncAttr<'a, &'o Order> for Total {
+        type Output = usize;
+
+        fn name(&self) -> &str {
+            "total"
+        }
+
+        fn get(&'a self, order: &'o Order) -> BoxFuture<'a, usize> {
+            delayed(order.items.len())
+        }
+    }
+
+    impl<'a> Attr<&'a Shipment> for OrderField {
+        type Output = &'a Order;
+
+        fn name(&self) -> &str {
+            "order"
+        }
+
+        fn get(&self, shipment: &'a Shipment) -> &'a Order {
+            &shipment.order
+        }
+    }
+
+    impl<'a> Attr<&'a Customer> for Email {
+        type Output = &'a str;
+
+        fn name(&self) -> &str {
+            "email"
+        }
+
+        fn get(&self, customer: &'a Customer) -> &'a str {
+            &customer.email
+        }
+    }
+
+    impl<'a> Attr<&'a Product> for Price {
+        type Output = u32;
+
+        fn name(&self) -> &str {
+            "price"
+        }
+
+        fn get(&self, product: &'a Product) -> u32 {
+            product.price
+        }
+    }
+
+    impl<'a> Attr<&'a Order> for Items {
+        type Output = &'a [u32];
+
+        fn name(&self) -> &str {
+            "items"
+        }
+
+        fn get(&self, order: &'a Order) -> &'a [u32] {
+            &order.items
+        }
+    }
+
+    impl<'a> IterableAttr<'a, &'a Order> for Items {
+        type Item = &'a u32;
+
+        fn iter(&self, order: &'a Order) -> Box<dyn Iterator<Item=&'a u32> + 'a> {
+            Box::new(order.items.iter())
+        }
+    }
+}
+
+fn store() -> Store {
+    let mut store = Store::default();
+    store.customers.insert(1, Customer { email: "flo@example.com".to_string() });
+    store.products.insert(10, Product { price: 5 });
+    store.products.insert(11, Product { price: 7 });
+    store
+}
+
+#[test]
+fn mixed_segments() {
+    let store = store();
+    let order = Order { customer: 1, items: vec![10, 11] };
+
+    let path = retrieve(Email).try_async(CustomerOf(&store));
+    assert_eq!(block_on(path.traverse_async(&order)).unwrap(), "flo@example.com");
+    assert_eq!(path.to_string(), "customer.email");
+
+    let unknown = Order { customer: 2, items: vec![] };
+    let error = block_on(path.traverse_async(&unknown)).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::Missing);
+    assert_eq!(error.message(), "customer 2 not found");
+
+    let total = retrieve_async(Total);
+    assert_eq!(block_on(total.traverse_async(&order)).unwrap(), 2);
+
+    let shipment = Shipment { order };
+    let path = retrieve(Email).try_async(CustomerOf(&store)).from(OrderField);
+    assert_eq!(block_on(path.traverse_async(&shipment)).unwrap(), "flo@example.com");
+    assert_eq!(path.to_string(), "order.customer.email");
+}
+
+#[test]
+fn sync_paths_traverse_asynchronously() {
+    let order = Order { customer: 1, items: vec![10, 11] };
+
+    let path = retrieve(Items);
+    assert_eq!(block_on(path.traverse_async(&order)).unwrap(), &[10, 11]);
+}
+
+#[test]
+fn streams() {
+    let store = store();
+    let order = Order { customer: 1, items: vec![10, 11, 12] };
+
+    let path = retrieve(Price).try_async(ProductOf(&store)).mapped_async(Items);
+    assert_eq!(path.to_string(), "items[*].product.price");
+    assert!(path.is_fallible());
+
+    let mut stream = block_on(path.traverse_async(&order)).unwrap();
+    let prices = block_on(stream.by_ref().collect::<Vec<_>>());
+    assert_eq!(block_on(stream.next()), None);
+
+    assert_eq!(prices[0], Ok(5));
+    assert_eq!(prices[1], Ok(7));
+    assert!(prices[2].as_ref().unwrap_err().is_missing());
+}
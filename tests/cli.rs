@@ -0,0 +1,47 @@
+#![cfg(feature = "cli")]
+
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+fn attr(args: &[&str], input: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_attr"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    // Invalid queries exit before reading the input.
+    let _ = child.stdin.take().unwrap().write_all(input.as_bytes());
+    child.wait_with_output().unwrap()
+}
+
+#[test]
+fn prints_matches_as_json_lines() {
+    let input = r#"{ "user": { "orders": [{ "id": 1, "tags": ["a"] }, { "id": 2, "tags": [] }] } }
+                   { "user": { "orders": [] } }"#;
+
+    let output = attr(&["user.orders[*].tags"], input);
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "[\"a\"]\n[]\n");
+
+    let output = attr(&["/user/orders"], input);
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "[{\"id\":1,\"tags\":[\"a\"]},{\"id\":2,\"tags\":[]}]\n[]\n");
+}
+
+#[test]
+fn reports_failures() {
+    let output = attr(&["$.user.orders[1].id"], r#"{ "user": { "orders": [{ "id": 1 }] } }"#);
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(String::from_utf8(output.stderr).unwrap(), "attr: <stdin>: document 0: user.orders[1]: index 1 is out of bounds\n");
+
+    let output = attr(&["user..id"], "{}");
+    assert_eq!(output.status.code(), Some(2));
+
+    let output = attr(&["user"], "{ nope");
+    assert_eq!(output.status.code(), Some(2));
+
+    let output = attr(&["user", "does/not/exist.json"], "");
+    assert_eq!(output.status.code(), Some(2));
+}
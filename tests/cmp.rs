@@ -0,0 +1,185 @@
+extern crate attr;
+
+use attr::retrieve;
+use attr::retrieve_insecure;
+use attr::cmp::*;
+
+#[derive(Debug)]
+pub struct User {
+    name: String,
+    age: u32,
+    team: Option<String>,
+}
+
+pub mod user {
+    use attr::Attr;
+    use attr::InsecureAttr;
+    use attr::Error;
+    use attr::Result;
+
+    use super::User;
+
+    pub struct Name;
+    pub struct Age;
+    pub struct Team;
+
+    impl<'a> Attr<&'a User> for Name {
+        type Output = &'a str;
+
+        fn get(&self, i: &'a User) -> &'a str {
+            i.name.as_ref()
+        }
+
+        fn name(&self) -> &'static str {
+            "name"
+        }
+    }
+
+    impl<'a> Attr<&'a User> for Age {
+        type Output = u32;
+
+        fn get(&self, i: &'a User) -> u32 {
+            i.age
+        }
+
+        fn name(&self) -> &'static str {
+            "age"
+        }
+    }
+
+    impl<'a> InsecureAttr<&'a User> for Team {
+        type Output = &'a str;
+
+        fn get(&self, i: &'a User) -> Result<&'a str> {
+            i.team.as_ref().map(|t| t.as_ref()).ok_or_else(|| Error::missing(format!("{} has no team", i.name)))
+        }
+
+        fn name(&self) -> &'static str {
+            "team"
+        }
+    }
+}
+
+fn user(name: &str, age: u32, team: Option<&str>) -> User {
+    User { name: name.into(), age, team: team.map(Into::into) }
+}
+
+fn users() -> Vec<User> {
+    vec![
+        user("carol", 30, Some("ops")),
+        user("alice", 25, Some("dev")),
+        user("bob", 30, Some("dev")),
+        user("dave", 25, Some("ops")),
+    ]
+}
+
+fn names(users: &[&User]) -> Vec<String> {
+    users.iter().map(|u| u.name.clone()).collect()
+}
+
+#[test]
+fn sort_by_single_key() {
+    let users = users();
+    let mut refs = users.iter().collect::<Vec<_>>();
+
+    let order = by_path(retrieve(user::Name));
+    let mut error = None;
+    refs.sort_by(comparator(&order, &mut error));
+
+    assert!(error.is_none());
+    assert_eq!(names(&refs), vec!["alice", "bob", "carol", "dave"]);
+}
+
+#[test]
+fn sort_by_multiple_keys() {
+    let users = users();
+    let mut refs = users.iter().collect::<Vec<_>>();
+
+    let order = (by_path(retrieve(user::Age)).desc(), by_path(retrieve(user::Name)).asc());
+    sort(&mut refs, &order).unwrap();
+
+    assert_eq!(names(&refs), vec!["bob", "carol", "alice", "dave"]);
+}
+
+#[test]
+fn sort_surfaces_errors() {
+    let mut users = users();
+    users.push(user("eve", 40, None));
+    let mut refs = users.iter().collect::<Vec<_>>();
+
+    let order = by_path(retrieve_insecure(user::Team));
+    let error = sort(&mut refs, &order).unwrap_err();
+
+    assert!(error.is_missing());
+    assert_eq!(error.message(), "eve has no team");
+    assert_eq!(error.index(), Some(4));
+}
+
+fn many_users() -> Vec<User> {
+    (0..100).map(|i| {
+        let team = if i % 4 == 1 { None } else { Some(format!("team{}", (i * 37) % 11)) };
+        user(&format!("user{}", i), i, team.as_ref().map(|t| t.as_ref()))
+    }).collect()
+}
+
+#[test]
+fn sort_surfaces_errors_of_large_inputs() {
+    let users = many_users();
+    let mut refs = users.iter().collect::<Vec<_>>();
+
+    let order = by_path(retrieve_insecure(user::Team));
+    let error = sort(&mut refs, &order).unwrap_err();
+
+    assert_eq!(error.index(), Some(1));
+    assert_eq!(error.message(), "user1 has no team");
+    assert_eq!(names(&refs), users.iter().map(|u| u.name.clone()).collect::<Vec<_>>());
+}
+
+#[test]
+fn comparator_orders_failures_last() {
+    let users = many_users();
+    let mut refs = users.iter().collect::<Vec<_>>();
+
+    let order = by_path(retrieve_insecure(user::Team));
+    let mut error = None;
+    refs.sort_by(comparator(&order, &mut error));
+
+    assert!(error.unwrap().is_missing());
+    let teams = refs.iter().map(|u| u.team.clone()).collect::<Vec<_>>();
+    assert!(teams[..75].iter().all(Option::is_some));
+    assert!(teams[75..].iter().all(Option::is_none));
+    assert!(teams[..75].windows(2).all(|w| w[0] <= w[1]));
+}
+
+#[test]
+fn group() {
+    let users = users();
+    let path = retrieve_insecure(user::Team);
+
+    let groups = group_by_path(&users, &path).unwrap();
+
+    assert_eq!(groups.keys().cloned().collect::<Vec<_>>(), vec!["dev", "ops"]);
+    assert_eq!(names(&groups["dev"]), vec!["alice", "bob"]);
+    assert_eq!(names(&groups["ops"]), vec!["carol", "dave"]);
+
+    let mut users = users;
+    users[2].team = None;
+    let path = retrieve_insecure(user::Team);
+    let error = group_by_path(&users, &path).unwrap_err();
+    assert_eq!(error.index(), Some(2));
+}
+
+#[test]
+fn dedup() {
+    let users = users();
+    let mut refs = users.iter().collect::<Vec<_>>();
+
+    let path = retrieve(user::Age);
+    dedup_by_path(&mut refs, &path).unwrap();
+    assert_eq!(names(&refs), vec!["carol", "alice", "bob", "dave"]);
+
+    let order = by_path(retrieve(user::Age));
+    sort(&mut refs, &order).unwrap();
+    dedup_by_path(&mut refs, &path).unwrap();
+    assert_eq!(names(&refs), vec!["alice", "carol"]);
+}
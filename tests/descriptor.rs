@@ -0,0 +1,170 @@
+extern crate attr;
+#[cfg(feature = "serde")]
+extern crate serde_json;
+
+use attr::retrieve;
+use attr::PathDescriptor;
+use attr::PathInfo;
+use attr::Registry;
+use attr::SegmentKind;
+use attr::Traverse;
+
+pub struct Foo {
+    batz: Bla,
+    items: Vec<Bla>,
+}
+
+pub struct Bla {
+    name: String,
+    count: u32,
+}
+
+pub mod foo {
+    use attr::Attr;
+    use attr::IterableAttr;
+
+    use super::{Foo, Bla};
+
+    pub struct Batz;
+    pub struct Items;
+
+    impl<'a> Attr<&'a Foo> for Batz {
+        type Output = &'a Bla;
+
+        fn get(&self, i: &'a Foo) -> &'a Bla {
+            &i.batz
+        }
+
+        fn name(&self) -> &'static str {
+            "batz"
+        }
+    }
+
+    impl<'a> Attr<&'a Foo> for Items {
+        type Output = &'a [Bla];
+
+        fn get(&self, i: &'a Foo) -> &'a [Bla] {
+            i.items.as_ref()
+        }
+
+        fn name(&self) -> &'static str {
+            "items"
+        }
+    }
+
+    impl<'a> IterableAttr<'a, &'a Foo> for Items {
+        type Item = &'a Bla;
+
+        fn iter(&self, i: &'a Foo) -> Box<dyn Iterator<Item=&'a Bla> + 'a> {
+            Box::new(self.get(i).iter())
+        }
+    }
+}
+
+pub mod bla {
+    use attr::Attr;
+
+    use super::Bla;
+
+    pub struct Name;
+    pub struct Count;
+
+    impl<'a> Attr<&'a Bla> for Name {
+        type Output = &'a str;
+
+        fn get(&self, i: &'a Bla) -> &'a str {
+            i.name.as_ref()
+        }
+
+        fn name(&self) -> &'static str {
+            "name"
+        }
+    }
+
+    impl<'a> Attr<&'a Bla> for Count {
+        type Output = u32;
+
+        fn get(&self, i: &'a Bla) -> u32 {
+            i.count
+        }
+
+        fn name(&self) -> &'static str {
+            "count"
+        }
+    }
+}
+
+fn registry() -> Registry {
+    let mut registry = Registry::new();
+    registry.register(foo::Batz)
+        .register_iterable(foo::Items)
+        .register(bla::Name)
+        .register(bla::Count);
+    registry
+}
+
+fn foo() -> Foo {
+    Foo {
+        batz: Bla { name: "batz".into(), count: 1 },
+        items: vec![Bla { name: "first".into(), count: 2 }, Bla { name: "second".into(), count: 3 }],
+    }
+}
+
+#[test]
+fn resolve_static_path() {
+    let foo = foo();
+    let path = retrieve(bla::Name).from(foo::Batz);
+    assert_eq!(path.traverse(&foo), Ok("batz"));
+
+    let descriptor = path.descriptor();
+    assert_eq!(descriptor.to_string(), "batz.name");
+
+    let dynamic = registry().resolve(&descriptor).unwrap();
+    let values = dynamic.traverse(&foo).unwrap();
+
+    assert_eq!(values.len(), 1);
+    assert_eq!(values[0].downcast_ref::<String>(), Some(&"batz".to_string()));
+}
+
+#[test]
+fn resolve_iterating_path() {
+    let foo = foo();
+    let descriptor = PathDescriptor::new()
+        .segment("items", SegmentKind::Iterable)
+        .segment("count", SegmentKind::Attr);
+
+    let dynamic = registry().resolve(&descriptor).unwrap();
+    assert_eq!(dynamic.to_string(), "items[*].count");
+
+    let counts = dynamic.traverse(&foo).unwrap().iter().map(|v| *v.downcast_ref::<u32>().unwrap()).collect::<Vec<_>>();
+    assert_eq!(counts, vec![2, 3]);
+}
+
+#[test]
+fn resolve_unknown() {
+    let descriptor = PathDescriptor::new().segment("batz", SegmentKind::Attr).segment("email", SegmentKind::Attr);
+    assert!(registry().resolve(&descriptor).is_err());
+
+    let descriptor = PathDescriptor::new().segment("batz", SegmentKind::Iterable);
+    assert!(registry().resolve(&descriptor).is_err());
+
+    let descriptor = PathDescriptor::new().segment("name", SegmentKind::Attr);
+    let dynamic = registry().resolve(&descriptor).unwrap();
+    assert!(dynamic.traverse(&foo()).is_err());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serialize_descriptor() {
+    let descriptor = PathDescriptor::new().segment("items", SegmentKind::Iterable).segment("name", SegmentKind::Attr);
+
+    let json = serde_json::to_string(&descriptor).unwrap();
+    assert_eq!(json, r#"{"segments":[{"name":"items","kind":"Iterable"},{"name":"name","kind":"Attr"}]}"#);
+
+    let restored: PathDescriptor = serde_json::from_str(&json).unwrap();
+    assert_eq!(restored, descriptor);
+
+    let foo = foo();
+    let names = registry().resolve(&restored).unwrap().traverse(&foo).unwrap();
+    assert_eq!(names[1].downcast_ref::<String>(), Some(&"second".to_string()));
+}
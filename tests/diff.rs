@@ -0,0 +1,221 @@
+extern crate attr;
+#[cfg(feature = "serde")]
+extern crate serde;
+
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+use attr::retrieve;
+use attr::retrieve_insecure;
+use attr::diff::*;
+
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct User {
+    name: String,
+    nickname: Option<String>,
+    data: Data,
+    tags: Vec<Tag>,
+}
+
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct Data {
+    email: String,
+}
+
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct Tag {
+    label: String,
+}
+
+pub mod user {
+    use attr::Attr;
+    use attr::InsecureAttr;
+    use attr::IterableAttr;
+    use attr::Error;
+    use attr::Result;
+
+    use super::{User, Data, Tag};
+
+    pub struct Name;
+    pub struct Nickname;
+    pub struct DataField;
+    pub struct Email;
+    pub struct Tags;
+    pub struct Label;
+
+    impl<'a> Attr<&'a User> for Name {
+        type Output = &'a str;
+
+        fn get(&self, i: &'a User) -> &'a str {
+            i.name.as_ref()
+        }
+
+        fn name(&self) -> &'static str {
+            "name"
+        }
+    }
+
+    impl<'a> InsecureAttr<&'a User> for Nickname {
+        type Output = &'a str;
+
+        fn get(&self, i: &'a User) -> Result<&'a str> {
+            i.nickname.as_ref().map(|n| n.as_ref()).ok_or_else(|| Error::missing("nickname is not set"))
+        }
+
+        fn name(&self) -> &'static str {
+            "nickname"
+        }
+    }
+
+    impl<'a> Attr<&'a User> for DataField {
+        type Output = &'a Data;
+
+        fn get(&self, i: &'a User) -> &'a Data {
+            &i.data
+        }
+
+        fn name(&self) -> &'static str {
+            "data"
+        }
+    }
+
+    impl<'a> Attr<&'a Data> for Email {
+        type Output = &'a str;
+
+        fn get(&self, i: &'a Data) -> &'a str {
+            i.email.as_ref()
+        }
+
+        fn name(&self) -> &'static str {
+            "email"
+        }
+    }
+
+    impl<'a> Attr<&'a User> for Tags {
+        type Output = &'a [Tag];
+
+        fn get(&self, i: &'a User) -> &'a [Tag] {
+            i.tags.as_ref()
+        }
+
+        fn name(&self) -> &'static str {
+            "tags"
+        }
+    }
+
+    impl<'a> IterableAttr<'a, &'a User> for Tags {
+        type Item = &'a Tag;
+
+        fn iter(&self, i: &'a User) -> Box<dyn Iterator<Item=&'a Tag> + 'a> {
+            Box::new(self.get(i).iter())
+        }
+    }
+
+    impl<'a> Attr<&'a Tag> for Label {
+        type Output = &'a str;
+
+        fn get(&self, i: &'a Tag) -> &'a str {
+            i.label.as_ref()
+        }
+
+        fn name(&self) -> &'static str {
+            "label"
+        }
+    }
+}
+
+fn user() -> User {
+    User {
+        name: "Florian".into(),
+        nickname: None,
+        data: Data { email: "florian@example.com".into() },
+        tags: vec![Tag { label: "rust".into() }],
+    }
+}
+
+#[test]
+fn unchanged() {
+    let old = user();
+    let new = user();
+
+    let paths = (
+        field(retrieve(user::Name)),
+        field(retrieve(user::Email).from(user::DataField)),
+    );
+
+    assert!(paths.diff(&old, &new).unwrap().is_empty());
+}
+
+#[test]
+fn changed_fields() {
+    let old = user();
+    let mut new = user();
+    new.data.email = "flo@example.com".into();
+    new.nickname = Some("flo".into());
+
+    let paths = (
+        field(retrieve(user::Name)),
+        field(retrieve_insecure(user::Nickname)),
+        field(retrieve(user::Email).from(user::DataField)),
+    );
+
+    let changes = paths.diff(&old, &new).unwrap();
+    let messages = changes.iter().map(|c| c.to_string()).collect::<Vec<_>>();
+
+    assert_eq!(messages, vec![
+        "nickname was set to \"flo\"",
+        "data.email changed from \"florian@example.com\" to \"flo@example.com\"",
+    ]);
+    assert_eq!(changes[1].old, Some("\"florian@example.com\"".to_string()));
+}
+
+#[test]
+fn changed_items() {
+    let old = user();
+    let mut new = user();
+    new.tags[0].label = "rustlang".into();
+    new.tags.push(Tag { label: "berlin".into() });
+
+    let paths = vec![
+        each(retrieve(user::Label).mapped(user::Tags)),
+    ];
+
+    let changes = paths.diff(&old, &new).unwrap();
+    let paths = changes.iter().map(|c| c.path.as_ref()).collect::<Vec<&str>>();
+
+    assert_eq!(paths, vec!["tags[0].label", "tags[1].label"]);
+    assert_eq!(changes[1].old, None);
+    assert_eq!(changes[1].new, Some("\"berlin\"".to_string()));
+
+    let changes = describe(&new, &old);
+    assert_eq!(changes, vec!["tags[0].label changed from \"rustlang\" to \"rust\"", "tags[1].label was removed, it was \"berlin\""]);
+}
+
+fn describe(old: &User, new: &User) -> Vec<String> {
+    let path = each(retrieve(user::Label).mapped(user::Tags));
+    path.diff(old, new).unwrap().iter().map(|c| c.to_string()).collect()
+}
+
+#[test]
+#[cfg(feature = "serde")]
+fn all_fields() {
+    let old = user();
+    let mut new = user();
+    new.data.email = "flo@example.com".into();
+    new.nickname = Some("flo".into());
+    new.tags.push(Tag { label: "berlin".into() });
+
+    assert!(all().diff(&old, &old).unwrap().is_empty());
+
+    let changes = all().diff(&old, &new).unwrap();
+    let messages = changes.iter().map(|c| c.to_string()).collect::<Vec<_>>();
+
+    assert_eq!(messages, vec![
+        "data.email changed from \"florian@example.com\" to \"flo@example.com\"",
+        "nickname was set to \"flo\"",
+        "tags[1] was set to {\"label\": \"berlin\"}",
+    ]);
+}
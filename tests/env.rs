@@ -0,0 +1,144 @@
+extern crate attr;
+
+use attr::env::{self, Bind, Bindings};
+
+#[derive(Debug, Default, PartialEq)]
+pub struct Config {
+    port: u16,
+    debug: bool,
+    data: Data,
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct Data {
+    email: String,
+    name: Option<String>,
+}
+
+pub mod config {
+    use attr::Attr;
+
+    use super::{Config, Data};
+
+    pub struct Port;
+    pub struct Debug;
+    pub struct DataField;
+    pub struct Email;
+    pub struct Name;
+
+    impl<'a> Attr<&'a mut Config> for Port {
+        type Output = &'a mut u16;
+
+        fn get(&self, i: &'a mut Config) -> &'a mut u16 {
+            &mut i.port
+        }
+
+        fn name(&self) -> &'static str {
+            "port"
+        }
+    }
+
+    impl<'a> Attr<&'a mut Config> for Debug {
+        type Output = &'a mut bool;
+
+        fn get(&self, i: &'a mut Config) -> &'a mut bool {
+            &mut i.debug
+        }
+
+        fn name(&self) -> &'static str {
+            "debug"
+        }
+    }
+
+    impl<'a> Attr<&'a mut Config> for DataField {
+        type Output = &'a mut Data;
+
+        fn get(&self, i: &'a mut Config) -> &'a mut Data {
+            &mut i.data
+        }
+
+        fn name(&self) -> &'static str {
+            "data"
+        }
+    }
+
+    impl<'a> Attr<&'a mut Data> for Email {
+        type Output = &'a mut String;
+
+        fn get(&self, i: &'a mut Data) -> &'a mut String {
+            &mut i.email
+        }
+
+        fn name(&self) -> &'static str {
+            "email"
+        }
+    }
+
+    impl<'a> Attr<&'a mut Data> for Name {
+        type Output = &'a mut Option<String>;
+
+        fn get(&self, i: &'a mut Data) -> &'a mut Option<String> {
+            &mut i.name
+        }
+
+        fn name(&self) -> &'static str {
+            "name"
+        }
+    }
+}
+
+impl Bind for Config {
+    fn bindings() -> Bindings<Config> {
+        Bindings::new()
+            .field(config::Port)
+            .field(config::Debug)
+            .nested(config::DataField, Bindings::new()
+                .field(config::Email)
+                .optional(config::Name))
+    }
+}
+
+fn vars(vars: &[(&str, &str)]) -> Vec<(String, String)> {
+    vars.iter().map(|&(k, v)| (k.to_string(), v.to_string())).collect()
+}
+
+#[test]
+fn binds_nested_attributes() {
+    let mut config = Config::default();
+
+    Config::bindings().load(&mut config, "APP_", vars(&[
+        ("APP_PORT", "8080"),
+        ("APP_DATA__EMAIL", "flo@example.com"),
+        ("APP_DATA__NAME", "Flo"),
+        ("OTHER_PORT", "1"),
+    ])).unwrap();
+
+    assert_eq!(config, Config {
+        port: 8080,
+        debug: false,
+        data: Data { email: "flo@example.com".to_string(), name: Some("Flo".to_string()) },
+    });
+}
+
+#[test]
+fn reports_problems_by_path() {
+    let mut config = Config::default();
+
+    let report = Config::bindings().load(&mut config, "APP_", vars(&[
+        ("APP_PORT", "eighty"),
+        ("APP_DEBUG", "true"),
+        ("APP_DATA__MAIL", "flo@example.com"),
+    ])).unwrap_err();
+
+    assert_eq!(report.to_string(), "data.mail: APP_DATA__MAIL does not match any attribute\nport: APP_PORT: invalid digit found in string");
+    assert!(config.debug);
+}
+
+#[test]
+fn loads_from_the_environment() {
+    std::env::set_var("ATTR_ENV_TEST_DATA__EMAIL", "env@example.com");
+
+    let mut config = Config::default();
+    env::load_into(&mut config, "ATTR_ENV_TEST_").unwrap();
+    assert_eq!(config.data.email, "env@example.com");
+}
@@ -0,0 +1,101 @@
+extern crate serde_json;
+extern crate attr;
+
+mod serde;
+
+use serde::*;
+
+use serde_json as json;
+use serde_json::value::Value;
+
+use attr::retrieve_insecure;
+use attr::{Traverse, SecureTraverse};
+
+fn config() -> Value {
+    json::from_str(r#"{"settings": {"retries": 3}, "defaults": {"timeout": 30, "retries": 1}}"#).unwrap()
+}
+
+#[test]
+fn fallback_path() {
+    let config = config();
+
+    let timeout = retrieve_insecure(SerdeAttribute::new("timeout")).try(SerdeAttribute::new("settings"))
+        .or(retrieve_insecure(SerdeAttribute::new("timeout")).try(SerdeAttribute::new("defaults")));
+    let retries = retrieve_insecure(SerdeAttribute::new("retries")).try(SerdeAttribute::new("settings"))
+        .or(retrieve_insecure(SerdeAttribute::new("retries")).try(SerdeAttribute::new("defaults")));
+
+    assert_eq!(timeout.traverse(&config), Ok(&Value::from(30)));
+    assert_eq!(retries.traverse(&config), Ok(&Value::from(3)));
+}
+
+#[test]
+fn fallback_fails_if_all_fail() {
+    let config = config();
+
+    let path = retrieve_insecure(SerdeAttribute::new("verbose")).try(SerdeAttribute::new("settings"))
+        .or(retrieve_insecure(SerdeAttribute::new("verbose")).try(SerdeAttribute::new("defaults")));
+
+    assert!(path.traverse(&config).is_err());
+}
+
+#[test]
+fn default_value() {
+    let config = config();
+    let fallback = Value::Bool(false);
+
+    let verbose = retrieve_insecure(SerdeAttribute::new("verbose")).try(SerdeAttribute::new("settings"))
+        .or(retrieve_insecure(SerdeAttribute::new("verbose")).try(SerdeAttribute::new("defaults")))
+        .or_value(&fallback);
+    let retries = retrieve_insecure(SerdeAttribute::new("retries")).try(SerdeAttribute::new("settings"))
+        .or_value(&fallback);
+
+    assert_eq!(verbose.get(&config), &Value::Bool(false));
+    assert_eq!(retries.get(&config), &Value::from(3));
+    assert_eq!(retries.traverse(&config), Ok(&Value::from(3)));
+}
+
+#[test]
+fn default_of_output() {
+    use attr::InsecureAttr;
+    use attr::Result;
+
+    struct AsU64;
+
+    impl<'a> InsecureAttr<&'a Value> for AsU64 {
+        type Output = u64;
+
+        fn name(&self) -> &str {
+            "as_u64"
+        }
+
+        fn get(&self, i: &'a Value) -> Result<u64> {
+            i.as_u64().ok_or_else(|| "not an unsigned number".into())
+        }
+    }
+
+    let config = config();
+
+    let timeout = retrieve_insecure(AsU64).try(SerdeAttribute::new("timeout")).try(SerdeAttribute::new("settings"))
+        .or_default();
+    let retries = retrieve_insecure(AsU64).try(SerdeAttribute::new("retries")).try(SerdeAttribute::new("settings"))
+        .or_default();
+
+    let timeout: u64 = timeout.get(&config);
+    assert_eq!(timeout, 0);
+    assert_eq!(retries.get(&config), 3);
+}
+
+#[test]
+fn fallback_reports_malformed_data() {
+    let config: Value = json::from_str(r#"{"settings": {"timeout": 5}, "defaults": {"timeout": 30}}"#).unwrap();
+
+    let path = retrieve_insecure(SerdeAttribute::new("timeout")).try(SerdeAttribute::new("timeout")).try(SerdeAttribute::new("settings"))
+        .or(retrieve_insecure(SerdeAttribute::new("timeout")).try(SerdeAttribute::new("defaults")));
+    let error = path.traverse(&config).unwrap_err();
+    assert_eq!(error.kind(), attr::ErrorKind::Invalid);
+
+    let fallback = Value::Null;
+    let path = retrieve_insecure(SerdeAttribute::new("timeout")).try(SerdeAttribute::new("timeout")).try(SerdeAttribute::new("settings"))
+        .or_value(&fallback);
+    assert_eq!(path.get(&config), &Value::Null);
+}
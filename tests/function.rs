@@ -0,0 +1,113 @@
+extern crate attr;
+
+use attr::attr_fn;
+use attr::insecure_attr_fn;
+use attr::retrieve;
+use attr::Error;
+use attr::Traverse;
+
+pub struct User {
+    data: Data,
+}
+
+pub struct Data {
+    email: String,
+    joined: String,
+}
+
+pub mod user {
+    use attr::Attr;
+
+    use super::User;
+    use super::Data;
+
+    pub struct DataField;
+    pub struct Email;
+    pub struct Joined;
+
+    impl<'a> Attr<&'a User> for DataField {
+        type Output = &'a Data;
+
+        fn get(&self, i: &'a User) -> &'a Data {
+            &i.data
+        }
+
+        fn name(&self) -> &'static str {
+            "data"
+        }
+    }
+
+    impl<'a> Attr<&'a Data> for Email {
+        type Output = &'a str;
+
+        fn get(&self, i: &'a Data) -> &'a str {
+            i.email.as_ref()
+        }
+
+        fn name(&self) -> &'static str {
+            "email"
+        }
+    }
+
+    impl<'a> Attr<&'a Data> for Joined {
+        type Output = &'a str;
+
+        fn get(&self, i: &'a Data) -> &'a str {
+            i.joined.as_ref()
+        }
+
+        fn name(&self) -> &'static str {
+            "joined"
+        }
+    }
+}
+
+fn user() -> User {
+    User { data: Data { email: "Flo@Andersground.net".into(), joined: "2016-10-01".into() } }
+}
+
+fn parse_year(date: &str) -> attr::Result<u32> {
+    date.split('-').next().and_then(|y| y.parse().ok()).ok_or_else(|| Error::invalid(format!("{} is not a date", date)))
+}
+
+#[test]
+fn map_result() {
+    let user = user();
+
+    let path = retrieve(user::Email).from(user::DataField).map(|email: &str| email.to_lowercase());
+
+    assert_eq!(path.traverse(&user), Ok("flo@andersground.net".to_string()));
+}
+
+#[test]
+fn map_inside_path() {
+    let user = user();
+
+    let path = retrieve(user::Email).map(|email: &str| email.len()).from(user::DataField);
+
+    assert_eq!(path.traverse(&user), Ok(20));
+}
+
+#[test]
+fn and_then_result() {
+    let user = user();
+
+    let path = retrieve(user::Joined).from(user::DataField).and_then(parse_year);
+    assert_eq!(path.traverse(&user), Ok(2016));
+
+    let path = retrieve(user::Email).from(user::DataField).and_then(parse_year);
+    assert!(path.traverse(&user).is_err());
+}
+
+#[test]
+fn function_attributes() {
+    let user = user();
+
+    let lowercase = attr_fn("lowercase", |email: &str| email.to_lowercase());
+    let path = retrieve(lowercase).from(user::Email).from(user::DataField);
+    assert_eq!(path.traverse(&user), Ok("flo@andersground.net".to_string()));
+
+    let year = insecure_attr_fn("year", parse_year);
+    let path = retrieve(attr_fn("double", |y: u32| y * 2)).try(year).from(user::Joined).from(user::DataField);
+    assert_eq!(path.traverse(&user), Ok(4032));
+}
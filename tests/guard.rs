@@ -0,0 +1,254 @@
+extern crate attr;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::{Arc, Mutex, RwLock};
+
+use attr::guard::*;
+
+pub struct Service {
+    user: Rc<RefCell<User>>,
+    settings: Arc<RwLock<Settings>>,
+}
+
+pub struct User {
+    name: String,
+    session: Option<Arc<Mutex<Session>>>,
+}
+
+pub struct Session {
+    token: String,
+}
+
+pub struct Settings {
+    theme: String,
+}
+
+pub mod service {
+    use std::cell::RefCell;
+    use std::sync::{Mutex, RwLock};
+
+    use attr::Attr;
+    use attr::InsecureAttr;
+    use attr::Error;
+    use attr::Result;
+
+    use super::{Service, User, Session, Settings};
+
+    pub struct UserField;
+    pub struct SettingsField;
+    pub struct Name;
+    pub struct SessionField;
+    pub struct Token;
+    pub struct Theme;
+
+    impl<'a> Attr<&'a Service> for UserField {
+        type Output = &'a RefCell<User>;
+
+        fn get(&self, i: &'a Service) -> &'a RefCell<User> {
+            &i.user
+        }
+
+        fn name(&self) -> &'static str {
+            "user"
+        }
+    }
+
+    impl<'a> Attr<&'a Service> for SettingsField {
+        type Output = &'a RwLock<Settings>;
+
+        fn get(&self, i: &'a Service) -> &'a RwLock<Settings> {
+            &i.settings
+        }
+
+        fn name(&self) -> &'static str {
+            "settings"
+        }
+    }
+
+    impl<'a> Attr<&'a User> for Name {
+        type Output = &'a str;
+
+        fn get(&self, i: &'a User) -> &'a str {
+            i.name.as_ref()
+        }
+
+        fn name(&self) -> &'static str {
+            "name"
+        }
+    }
+
+    impl<'a> InsecureAttr<&'a User> for SessionField {
+        type Output = &'a Mutex<Session>;
+
+        fn get(&self, i: &'a User) -> Result<&'a Mutex<Session>> {
+            i.session.as_deref().ok_or_else(|| Error::missing("no session"))
+        }
+
+        fn name(&self) -> &'static str {
+            "session"
+        }
+    }
+
+    impl<'a> Attr<&'a Session> for Token {
+        type Output = &'a str;
+
+        fn get(&self, i: &'a Session) -> &'a str {
+            i.token.as_ref()
+        }
+
+        fn name(&self) -> &'static str {
+            "token"
+        }
+    }
+
+    impl<'a> Attr<&'a Settings> for Theme {
+        type Output = &'a str;
+
+        fn get(&self, i: &'a Settings) -> &'a str {
+            i.theme.as_ref()
+        }
+
+        fn name(&self) -> &'static str {
+            "theme"
+        }
+    }
+}
+
+fn service() -> Service {
+    Service {
+        user: Rc::new(RefCell::new(User {
+            name: "Florian".into(),
+            session: Some(Arc::new(Mutex::new(Session { token: "secret".into() }))),
+        })),
+        settings: Arc::new(RwLock::new(Settings { theme: "dark".into() })),
+    }
+}
+
+#[test]
+fn hold_keeps_guards() {
+    let service = service();
+    let path = guarded(service::Name).borrowed().from(service::UserField);
+
+    let name = path.hold(&service).unwrap();
+    assert_eq!(&*name, "Florian");
+    assert_eq!(name.guards(), 1);
+    assert!(service.user.try_borrow_mut().is_err());
+
+    drop(name);
+    assert!(service.user.try_borrow_mut().is_ok());
+}
+
+#[test]
+fn nested_guards() {
+    let service = service();
+    let path = guarded(service::Token).locked().try(service::SessionField).borrowed().from(service::UserField);
+
+    let token = path.hold(&service).unwrap();
+    assert_eq!(&*token, "secret");
+    assert_eq!(token.guards(), 2);
+    drop(token);
+
+    service.user.borrow_mut().session = None;
+    assert!(path.hold(&service).unwrap_err().is_missing());
+}
+
+#[test]
+fn with_closure() {
+    let service = service();
+    let path = guarded(service::Theme).read_locked().from(service::SettingsField);
+
+    assert_eq!(path.with(&service, |theme| theme.len()).unwrap(), 4);
+    assert!(service.settings.try_write().is_ok());
+
+    let settings = read_locked().from(service::SettingsField);
+    assert_eq!(settings.with(&service, |s| s.theme.clone()).unwrap(), "dark");
+}
+
+#[test]
+fn conflicting_borrows_fail() {
+    let service = service();
+    let path = guarded(service::Name).borrowed().from(service::UserField);
+
+    let _writer = service.user.borrow_mut();
+    assert!(path.hold(&service).is_err());
+}
+
+pub struct Borrowing<'s> {
+    name: &'s str,
+}
+
+pub struct Outer {
+    inner: Arc<Mutex<String>>,
+}
+
+pub mod nested {
+    use std::sync::Mutex;
+
+    use attr::Attr;
+
+    use super::{Borrowing, Outer};
+
+    pub struct Name;
+    pub struct Inner;
+
+    impl<'a, 's> Attr<&'a Borrowing<'s>> for Name {
+        type Output = &'a &'s str;
+
+        fn get(&self, i: &'a Borrowing<'s>) -> &'a &'s str {
+            &i.name
+        }
+
+        fn name(&self) -> &'static str {
+            "name"
+        }
+    }
+
+    impl<'a> Attr<&'a Outer> for Inner {
+        type Output = &'a Mutex<String>;
+
+        fn get(&self, i: &'a Outer) -> &'a Mutex<String> {
+            &i.inner
+        }
+
+        fn name(&self) -> &'static str {
+            "inner"
+        }
+    }
+}
+
+#[test]
+fn borrowing_targets() {
+    let name = String::from("flo");
+    let cell = RefCell::new(Borrowing { name: &name });
+
+    let path = guarded(nested::Name).borrowed();
+    assert_eq!(*path.hold(&cell).unwrap(), "flo");
+}
+
+#[test]
+fn guards_drop_in_reverse_order() {
+    let outer = Arc::new(Mutex::new(Outer { inner: Arc::new(Mutex::new("flo".into())) }));
+    let path = locked().from(nested::Inner).locked();
+
+    // The inner lock is only taken while holding the outer one. If the outer
+    // guard were released first, the other thread could take it while the
+    // inner lock is still held.
+    let other = {
+        let outer = outer.clone();
+        std::thread::spawn(move || {
+            for _ in 0..1000 {
+                let outer = outer.lock().unwrap();
+                assert!(outer.inner.try_lock().is_ok());
+            }
+        })
+    };
+
+    for _ in 0..1000 {
+        let held = path.hold(&*outer).unwrap();
+        assert_eq!(held.guards(), 2);
+        assert_eq!(&*held, "flo");
+    }
+
+    other.join().unwrap();
+}
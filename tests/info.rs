@@ -0,0 +1,143 @@
+extern crate serde_json;
+extern crate attr;
+
+mod serde;
+
+use serde::*;
+
+use attr::retrieve;
+use attr::retrieve_insecure;
+use attr::Identity;
+use attr::PathInfo;
+use attr::Segment;
+use attr::SegmentKind;
+use attr::Traverse;
+
+use serde_json::value::Value;
+
+pub struct Foo {
+    vector: Vec<Bla>,
+}
+
+pub struct Bla {
+    name: String,
+}
+
+pub struct Top {
+    foo: Foo,
+}
+
+pub mod attrs {
+    use attr::Attr;
+    use attr::IterableAttr;
+
+    use super::{Foo, Bla, Top};
+
+    pub struct FooField;
+    pub struct Vector;
+    pub struct Name;
+
+    impl<'a> Attr<&'a Top> for FooField {
+        type Output = &'a Foo;
+
+        fn get(&self, i: &'a Top) -> &'a Foo {
+            &i.foo
+        }
+
+        fn name(&self) -> &'static str {
+            "foo"
+        }
+    }
+
+    impl<'a> Attr<&'a Foo> for Vector {
+        type Output = &'a [Bla];
+
+        fn get(&self, i: &'a Foo) -> &'a [Bla] {
+            i.vector.as_ref()
+        }
+
+        fn name(&self) -> &'static str {
+            "vector"
+        }
+    }
+
+    impl<'a> IterableAttr<'a, &'a Foo> for Vector {
+        type Item = &'a Bla;
+
+        fn iter(&self, i: &'a Foo) -> Box<dyn Iterator<Item=&'a Bla> + 'a> {
+            Box::new(self.get(i).iter())
+        }
+    }
+
+    impl<'a> Attr<&'a Bla> for Name {
+        type Output = &'a str;
+
+        fn get(&self, i: &'a Bla) -> &'a str {
+            i.name.as_ref()
+        }
+
+        fn name(&self) -> &'static str {
+            "name"
+        }
+    }
+}
+
+#[test]
+fn plain_path() {
+    let path = retrieve(attrs::Name);
+    assert_eq!(path.traverse(&Bla { name: "bla".into() }), Ok("bla"));
+
+    assert_eq!(path.segments(), vec![Segment { name: "name", kind: SegmentKind::Attr }]);
+    assert_eq!(path.len(), 1);
+    assert!(!path.is_fallible());
+    assert!(!path.is_iterating());
+    assert_eq!(path.to_string(), "name");
+
+    assert!(Identity.is_empty());
+    assert_eq!(Identity.to_string(), "");
+}
+
+#[test]
+fn mapped_path() {
+    let top = Top { foo: Foo { vector: vec![Bla { name: "bla".into() }] } };
+    let path = retrieve(attrs::Name).mapped(attrs::Vector).from(attrs::FooField);
+    assert!(path.traverse(&top).is_ok());
+
+    assert_eq!(path.len(), 3);
+    assert!(path.is_iterating());
+    assert_eq!(path.segments()[1], Segment { name: "vector", kind: SegmentKind::Iterable });
+    assert_eq!(path.to_string(), "foo.vector[*].name");
+}
+
+#[test]
+fn insecure_path() {
+    let obj: Value = serde_json::from_str(r#"{"y": {"z": 1}}"#).unwrap();
+    let path = retrieve_insecure(SerdeAttribute::new("z")).try(SerdeAttribute::new("y"));
+    assert_eq!(path.traverse(&obj), Ok(&Value::from(1)));
+
+    assert!(path.is_fallible());
+    assert_eq!(path.segments().iter().map(|s| s.kind).collect::<Vec<_>>(), vec![SegmentKind::Insecure, SegmentKind::Insecure]);
+    assert_eq!(path.to_string(), "y.z");
+}
+
+#[test]
+fn combined_paths() {
+    let obj: Value = serde_json::from_str(r#"{"y": {"z": 1, "w": 2}}"#).unwrap();
+    let z = || retrieve_insecure(SerdeAttribute::new("z")).try(SerdeAttribute::new("y"));
+    let w = || retrieve_insecure(SerdeAttribute::new("w")).try(SerdeAttribute::new("y"));
+
+    let mapped = z().map(|v: &Value| v.as_u64());
+    assert_eq!(mapped.traverse(&obj), Ok(Some(1)));
+    assert_eq!(mapped.to_string(), "y.z");
+
+    let fallback = z().or(w());
+    assert_eq!(fallback.to_string(), "y.z");
+    assert_eq!(z().or_value(&obj).to_string(), "y.z");
+    assert_eq!(z().optional().to_string(), "y.z");
+
+    let zipped = attr::zip((z(), w()));
+    assert_eq!(zipped.traverse(&obj), Ok((&Value::from(1), &Value::from(2))));
+    assert_eq!(zipped.segments(), vec![Segment { name: "y", kind: SegmentKind::Insecure }]);
+    assert_eq!(zipped.to_string(), "(y.z, y.w)");
+    assert_eq!(zipped.descriptor().to_string(), "y");
+}
@@ -0,0 +1,113 @@
+extern crate attr;
+
+use attr::retrieve;
+use attr::Aggregate;
+use attr::Traverse;
+
+pub struct Company {
+    users: Vec<User>,
+}
+
+pub struct User {
+    address: Address,
+}
+
+pub struct Address {
+    city: String,
+}
+
+pub mod user {
+    use attr::{Attr, IterableAttr, Identity, Path, retrieve};
+
+    use super::{Company, User, Address};
+
+    pub struct AddressField;
+    pub struct Users;
+
+    impl<'a> Attr<&'a User> for AddressField {
+        type Output = &'a Address;
+
+        fn get(&self, i: &'a User) -> &'a Address {
+            &i.address
+        }
+
+        fn name(&self) -> &'static str {
+            "address"
+        }
+    }
+
+    impl<'a> Attr<&'a Company> for Users {
+        type Output = &'a [User];
+
+        fn get(&self, i: &'a Company) -> &'a [User] {
+            i.users.as_ref()
+        }
+
+        fn name(&self) -> &'static str {
+            "users"
+        }
+    }
+
+    impl<'a> IterableAttr<'a, &'a Company> for Users {
+        type Item = &'a User;
+
+        fn iter(&self, i: &'a Company) -> Box<dyn Iterator<Item=&'a User> + 'a> {
+            Box::new(self.get(i).iter())
+        }
+    }
+
+    pub fn address<'a>() -> Path<&'a User, &'a Address, AddressField, Identity> {
+        retrieve(AddressField)
+    }
+}
+
+pub mod address {
+    use attr::{Attr, Identity, Path, retrieve};
+
+    use super::Address;
+
+    pub struct City;
+
+    impl<'a> Attr<&'a Address> for City {
+        type Output = &'a str;
+
+        fn get(&self, i: &'a Address) -> &'a str {
+            i.city.as_ref()
+        }
+
+        fn name(&self) -> &'static str {
+            "city"
+        }
+    }
+
+    pub fn city<'a>() -> Path<&'a Address, &'a str, City, Identity> {
+        retrieve(City)
+    }
+}
+
+fn size_of<T>(_t: &T) -> usize {
+    std::mem::size_of::<T>()
+}
+
+fn user(city: &str) -> User {
+    User { address: Address { city: city.into() } }
+}
+
+#[test]
+fn join_paths() {
+    let user = user("Berlin");
+
+    let path = user::address().join(address::city());
+
+    assert_eq!(path.traverse(&user), Ok("Berlin"));
+    assert_eq!(size_of(&path), 0);
+}
+
+#[test]
+fn join_mapped() {
+    let company = Company { users: vec![user("Berlin"), user("Hamburg")] };
+
+    let path = retrieve(user::AddressField).mapped(user::Users).join(address::city());
+
+    assert_eq!(path.collect_vec(&company), Ok(vec!["Berlin", "Hamburg"]));
+}
@@ -0,0 +1,152 @@
+extern crate attr;
+extern crate serde_json;
+
+use attr::retrieve_insecure;
+use attr::Layered;
+use attr::ErrorKind;
+
+use config::*;
+
+#[derive(Default)]
+pub struct Config {
+    data: Option<Data>,
+    port: Option<u16>,
+}
+
+#[derive(Default)]
+pub struct Data {
+    email: Option<String>,
+}
+
+pub mod config {
+    use attr::{InsecureAttr, Error, Result};
+
+    use super::{Config, Data};
+
+    pub struct DataField;
+    pub struct Email;
+    pub struct Port;
+
+    impl<'a> InsecureAttr<&'a Config> for DataField {
+        type Output = &'a Data;
+
+        fn get(&self, i: &'a Config) -> Result<&'a Data> {
+            i.data.as_ref().ok_or_else(|| Error::missing("data is not set"))
+        }
+
+        fn name(&self) -> &'static str {
+            "data"
+        }
+    }
+
+    impl<'a> InsecureAttr<&'a Data> for Email {
+        type Output = &'a str;
+
+        fn get(&self, i: &'a Data) -> Result<&'a str> {
+            i.email.as_deref().ok_or_else(|| Error::missing("email is not set"))
+        }
+
+        fn name(&self) -> &'static str {
+            "email"
+        }
+    }
+
+    impl<'a> InsecureAttr<&'a Config> for Port {
+        type Output = u16;
+
+        fn get(&self, i: &'a Config) -> Result<u16> {
+            match i.port {
+                Some(0) => Err(Error::invalid("port 0 is reserved")),
+                Some(port) => Ok(port),
+                None => Err(Error::missing("port is not set")),
+            }
+        }
+
+        fn name(&self) -> &'static str {
+            "port"
+        }
+    }
+}
+
+fn config(email: Option<&str>, port: Option<u16>) -> Config {
+    Config {
+        data: email.map(|e| Data { email: Some(e.to_string()) }),
+        port,
+    }
+}
+
+#[test]
+fn first_hit_wins() {
+    let layers = Layered::new()
+        .layer("cli", config(None, Some(8080)))
+        .layer("env", Config { data: Some(Data::default()), port: None })
+        .layer("file", config(Some("file@example.com"), Some(80)))
+        .layer("defaults", config(Some("root@localhost"), Some(8000)));
+
+    let email = layers.get(&retrieve_insecure(Email).try(DataField)).unwrap();
+    assert_eq!(*email.value(), "file@example.com");
+    assert_eq!(email.layer(), "file");
+    assert_eq!(email.index(), 2);
+
+    let port = layers.get(&retrieve_insecure(Port)).unwrap();
+    assert_eq!(port.layer(), "cli");
+    assert_eq!(port.into_value(), 8080);
+
+    let trace = layers.trace(&retrieve_insecure(Email).try(DataField));
+    let sources = trace.iter().map(|&(layer, ref r)| (layer, r.is_ok())).collect::<Vec<_>>();
+    assert_eq!(sources, vec![("cli", false), ("env", false), ("file", true), ("defaults", true)]);
+}
+
+#[test]
+fn failures() {
+    let layers = Layered::new()
+        .layer("env", config(None, Some(0)))
+        .layer("defaults", config(None, Some(8000)));
+
+    let error = layers.get(&retrieve_insecure(Port)).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::Invalid);
+    assert_eq!(error.message(), "env: port 0 is reserved");
+
+    let error = layers.get(&retrieve_insecure(Email).try(DataField)).unwrap_err();
+    assert!(error.is_missing());
+    assert_eq!(error.message(), "not present in any of env, defaults");
+
+    assert_eq!(layers.names(), vec!["env", "defaults"]);
+    assert!(layers.find("defaults").is_some());
+}
+
+#[test]
+fn errors_keep_their_index() {
+    let error = attr::Error::invalid("port 0 is reserved").with_index(2).context("env");
+    assert_eq!(error.kind(), ErrorKind::Invalid);
+    assert_eq!(error.index(), Some(2));
+    assert_eq!(error.to_string(), "at index 2: env: port 0 is reserved");
+}
+
+#[test]
+#[cfg(feature = "serde")]
+fn sources_of_different_types() {
+    use std::collections::BTreeMap;
+    use attr::value::{Key, Value};
+
+    let env = vec![("PORT".to_string(), "9000".to_string())].into_iter().collect::<BTreeMap<_, _>>();
+    let server = vec![("host", "localhost"), ("port", "8000")].into_iter().collect::<BTreeMap<_, _>>();
+    let defaults = vec![("server", server)].into_iter().collect::<BTreeMap<_, _>>();
+    let file: serde_json::Value = serde_json::from_str(r#"{"server": {"host": "example.com"}}"#).unwrap();
+
+    let layers = Layered::new()
+        .source("env", &env).unwrap()
+        .source("file", &file).unwrap()
+        .source("defaults", &defaults).unwrap();
+
+    let host = layers.get(&retrieve_insecure(Key::new("host")).try(Key::new("server"))).unwrap();
+    assert_eq!(host.layer(), "file");
+    assert_eq!(*host.value(), &Value::from("example.com"));
+
+    let port = layers.get(&retrieve_insecure(Key::new("port")).try(Key::new("server"))).unwrap();
+    assert_eq!(port.layer(), "defaults");
+    assert_eq!(port.into_value(), &Value::from("8000"));
+
+    let env = layers.get(&retrieve_insecure(Key::new("PORT"))).unwrap();
+    assert_eq!(env.layer(), "env");
+}
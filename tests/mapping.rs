@@ -3,6 +3,7 @@ extern crate attr;
 use attr::retrieve;
 use attr::Traverse;
 
+#[allow(dead_code)]
 trait Attributes<AttributeType> {
     fn attrs() -> AttributeType;
 }
@@ -85,7 +86,7 @@ pub mod foo {
     impl<'a> IterableAttr<'a, &'a Foo> for Vector {
         type Item = &'a Bla;
 
-        fn iter(&self, i: &'a Foo) -> Box<Iterator<Item=&'a Bla> + 'a> {
+        fn iter(&self, i: &'a Foo) -> Box<dyn Iterator<Item=&'a Bla> + 'a> {
             Box::new(self.get(i).iter())
         }
     }
@@ -140,7 +140,7 @@ pub mod foo {
     impl<'a> IterableAttr<'a, &'a Foo> for Numbers {
         type Item = &'a i32;
 
-        fn iter(&self, i: &'a Foo) -> Box<Iterator<Item=&'a i32> + 'a> {
+        fn iter(&self, i: &'a Foo) -> Box<dyn Iterator<Item=&'a i32> + 'a> {
             Box::new(self.get(i).iter())
         }
     }
@@ -148,7 +148,7 @@ pub mod foo {
     impl<'a> IterableAttr<'a, &'a mut Foo> for Numbers {
         type Item = &'a mut i32;
 
-        fn iter(&self, i: &'a mut Foo) -> Box<Iterator<Item=&'a mut i32> +'a> {
+        fn iter(&self, i: &'a mut Foo) -> Box<dyn Iterator<Item=&'a mut i32> +'a> {
             Box::new(self.get(i).iter_mut())
         }
     }
@@ -249,7 +249,7 @@ fn nested_vec() {
 fn nested_vec_mutable() {
     let mut f = Foo { bar: "foobar".into(), batz: Bla { name: "foo".into() }, numbers: vec![1,2,3] };
     {
-        let mut x: &mut i32 = foo::Numbers.at(&mut f, 1);
+        let x: &mut i32 = foo::Numbers.at(&mut f, 1);
         *x = 4;
     }
     let y = foo::Numbers.at(&f, 1);
@@ -265,7 +265,7 @@ fn nested_filter() {
     let f = Foo { bar: "foobar".into(), batz: Bla { name: "foo".into() }, numbers: vec![1,2,3] };
     let f2 = Foo { bar: "foobar".into(), batz: Bla { name: "bar".into() }, numbers: vec![1,2,3] };
 
-    let vec = vec![f, f2];
+    let vec = [f, f2];
     let path = retrieve(Bla::attrs().name).from(Foo::attrs().batz);
 
     assert_eq!(size_of(&path),0);
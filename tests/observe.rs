@@ -0,0 +1,166 @@
+extern crate attr;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use attr::retrieve;
+use attr::Observable;
+use attr::Notification;
+
+pub struct User {
+    name: String,
+    data: Data,
+}
+
+#[derive(Clone, PartialEq)]
+pub struct Data {
+    email: String,
+    logins: u32,
+}
+
+pub mod user {
+    use attr::Attr;
+
+    use super::{User, Data};
+
+    pub struct Name;
+    pub struct DataField;
+    pub struct Email;
+    pub struct Logins;
+
+    impl<'a> Attr<&'a mut User> for Name {
+        type Output = &'a mut String;
+
+        fn get(&self, i: &'a mut User) -> &'a mut String {
+            &mut i.name
+        }
+
+        fn name(&self) -> &'static str {
+            "name"
+        }
+    }
+
+    impl<'a> Attr<&'a mut User> for DataField {
+        type Output = &'a mut Data;
+
+        fn get(&self, i: &'a mut User) -> &'a mut Data {
+            &mut i.data
+        }
+
+        fn name(&self) -> &'static str {
+            "data"
+        }
+    }
+
+    impl<'a> Attr<&'a mut Data> for Email {
+        type Output = &'a mut String;
+
+        fn get(&self, i: &'a mut Data) -> &'a mut String {
+            &mut i.email
+        }
+
+        fn name(&self) -> &'static str {
+            "email"
+        }
+    }
+
+    impl<'a> Attr<&'a mut Data> for Logins {
+        type Output = &'a mut u32;
+
+        fn get(&self, i: &'a mut Data) -> &'a mut u32 {
+            &mut i.logins
+        }
+
+        fn name(&self) -> &'static str {
+            "logins"
+        }
+    }
+}
+
+fn user() -> User {
+    User { name: "Florian".into(), data: Data { email: "flo@example.com".into(), logins: 0 } }
+}
+
+fn recorder(log: &Rc<RefCell<Vec<String>>>, tag: &'static str) -> impl FnMut(&Notification) + 'static {
+    let log = log.clone();
+    move |n: &Notification| log.borrow_mut().push(format!("{}: {}", tag, n.path()))
+}
+
+#[test]
+fn notifies_at_and_below_watched_paths() {
+    let log = Rc::new(RefCell::new(Vec::new()));
+    let mut user = Observable::new(user());
+
+    let email = retrieve(user::Email).from(user::DataField);
+    let logins = retrieve(user::Logins).from(user::DataField);
+    let name = retrieve(user::Name);
+
+    user.watch(&retrieve(user::DataField), recorder(&log, "data"));
+    user.watch(&email, recorder(&log, "email"));
+
+    user.set(&email, "florian@example.com".to_string()).unwrap();
+    user.modify(&logins, |n| *n += 1).unwrap();
+    user.set(&name, "Flo".to_string()).unwrap();
+    user.modify(&logins, |n| *n += 1).unwrap();
+
+    assert_eq!(*log.borrow(), vec![
+        "data: data.email",
+        "email: data.email",
+        "data: data.logins",
+        "data: data.logins",
+    ]);
+    assert_eq!(user.get().data.logins, 2);
+    assert_eq!(user.into_inner().name, "Flo");
+}
+
+#[test]
+fn passes_old_and_new_values() {
+    let seen = Rc::new(RefCell::new(None));
+    let mut user = Observable::new(user());
+
+    let email = retrieve(user::Email).from(user::DataField);
+    let record = seen.clone();
+    user.watch(&email, move |n: &Notification| {
+        *record.borrow_mut() = Some((n.old_value::<String>().cloned(), n.new_value::<String>().cloned()));
+    });
+
+    let old = user.set(&email, "florian@example.com".to_string()).unwrap();
+    assert_eq!(old, "flo@example.com");
+    assert_eq!(*seen.borrow(), Some((Some("flo@example.com".to_string()), Some("florian@example.com".to_string()))));
+}
+
+#[test]
+fn unchanged_values_and_removed_callbacks_stay_silent() {
+    let log = Rc::new(RefCell::new(Vec::new()));
+    let mut user = Observable::new(user());
+
+    let name = retrieve(user::Name);
+    let id = user.watch(&name, recorder(&log, "name"));
+
+    user.set(&name, "Florian".to_string()).unwrap();
+    assert!(log.borrow().is_empty());
+
+    assert!(user.unwatch(id));
+    assert!(!user.unwatch(id));
+    user.set(&name, "Flo".to_string()).unwrap();
+    assert!(log.borrow().is_empty());
+}
+
+#[test]
+fn replacing_a_parent_notifies_children() {
+    let seen = Rc::new(RefCell::new(Vec::new()));
+    let mut user = Observable::new(user());
+
+    let email = retrieve(user::Email).from(user::DataField);
+    let record = seen.clone();
+    user.watch(&email, move |n: &Notification| {
+        record.borrow_mut().push((n.path().to_string(), n.old_value::<String>().is_some(), n.new_value::<Data>().map(|d| d.email.clone())));
+    });
+    let log = Rc::new(RefCell::new(Vec::new()));
+    user.watch(&retrieve(user::Name), recorder(&log, "name"));
+
+    user.set(&retrieve(user::DataField), Data { email: "florian@example.com".into(), logins: 0 }).unwrap();
+
+    assert_eq!(*seen.borrow(), vec![("data".to_string(), false, Some("florian@example.com".to_string()))]);
+    assert!(log.borrow().is_empty());
+}
@@ -0,0 +1,42 @@
+extern crate serde_json;
+extern crate attr;
+
+mod serde;
+
+use serde::*;
+
+use serde_json as json;
+use serde_json::value::Value;
+
+use attr::retrieve_insecure;
+use attr::ErrorKind;
+use attr::Traverse;
+
+#[test]
+fn present_value() {
+    let patch: Value = json::from_str(r#"{"user": {"email": "flo@andersground.net"}}"#).unwrap();
+
+    let path = retrieve_insecure(SerdeAttribute::new("email")).try(SerdeAttribute::new("user")).optional();
+
+    assert_eq!(path.traverse(&patch), Ok(Some(&Value::String("flo@andersground.net".into()))));
+}
+
+#[test]
+fn absent_value() {
+    let patch: Value = json::from_str(r#"{"user": {"name": "Florian"}}"#).unwrap();
+
+    let path = retrieve_insecure(SerdeAttribute::new("email")).try(SerdeAttribute::new("user")).optional();
+    assert_eq!(path.traverse(&patch), Ok(None));
+
+    let path = retrieve_insecure(SerdeAttribute::new("email")).try(SerdeAttribute::new("account")).optional();
+    assert_eq!(path.traverse(&patch), Ok(None));
+}
+
+#[test]
+fn malformed_value() {
+    let patch: Value = json::from_str(r#"{"user": "Florian"}"#).unwrap();
+
+    let path = retrieve_insecure(SerdeAttribute::new("email")).try(SerdeAttribute::new("user")).optional();
+
+    assert_eq!(path.traverse(&patch).unwrap_err().kind(), ErrorKind::Invalid);
+}
@@ -0,0 +1,148 @@
+#![cfg(feature = "rayon")]
+
+extern crate attr;
+extern crate rayon;
+
+use rayon::prelude::*;
+
+use attr::retrieve;
+use attr::retrieve_insecure;
+use attr::ParTraverse;
+use attr::Traverse;
+
+use batch::*;
+
+pub struct Job {
+    batch: Batch,
+}
+
+pub struct Batch {
+    records: Vec<Record>,
+}
+
+pub struct Record {
+    value: u64,
+}
+
+pub mod batch {
+    use attr::{Attr, InsecureAttr, IterableAttr, Error, Result};
+
+    use super::{Job, Batch, Record};
+
+    pub struct BatchField;
+    pub struct Records;
+    pub struct Value;
+    pub struct Checked;
+    pub struct Worker;
+
+    impl<'a> Attr<&'a Job> for BatchField {
+        type Output = &'a Batch;
+
+        fn name(&self) -> &str {
+            "batch"
+        }
+
+        fn get(&self, job: &'a Job) -> &'a Batch {
+            &job.batch
+        }
+    }
+
+    impl<'a> Attr<&'a Batch> for Records {
+        type Output = &'a [Record];
+
+        fn name(&self) -> &str {
+            "records"
+        }
+
+        fn get(&self, batch: &'a Batch) -> &'a [Record] {
+            &batch.records
+        }
+    }
+
+    impl<'a> IterableAttr<'a, &'a Batch> for Records {
+        type Item = &'a Record;
+
+        fn iter(&self, batch: &'a Batch) -> Box<dyn Iterator<Item=&'a Record> + 'a> {
+            Box::new(batch.records.iter())
+        }
+    }
+
+    impl<'a> Attr<&'a Record> for Value {
+        type Output = u64;
+
+        fn name(&self) -> &str {
+            "value"
+        }
+
+        fn get(&self, record: &'a Record) -> u64 {
+            record.value
+        }
+    }
+
+    impl<'a> InsecureAttr<&'a Record> for Checked {
+        type Output = u64;
+
+        fn name(&self) -> &str {
+            "checked"
+        }
+
+        fn get(&self, record: &'a Record) -> Result<u64> {
+            if record.value % 100 == 99 {
+                Err(Error::invalid(format!("{} is not allowed", record.value)))
+            } else {
+                Ok(record.value)
+            }
+        }
+    }
+
+    impl<'a> Attr<&'a Record> for Worker {
+        type Output = Option<usize>;
+
+        fn name(&self) -> &str {
+            "worker"
+        }
+
+        fn get(&self, _record: &'a Record) -> Option<usize> {
+            rayon::current_thread_index()
+        }
+    }
+}
+
+fn batch(n: u64) -> Batch {
+    Batch { records: (0..n).map(|value| Record { value }).collect() }
+}
+
+#[test]
+fn matches_sequential_traversal() {
+    let batch = batch(10_000);
+    let path = retrieve(Value).mapped(Records);
+
+    let sequential = path.traverse(&batch).unwrap().collect::<Vec<_>>();
+    let parallel = path.par_traverse(&batch).unwrap().collect::<Vec<_>>();
+    assert_eq!(parallel, sequential);
+
+    let sum: u64 = path.par_traverse(&batch).unwrap().map(|v| v.unwrap()).sum();
+    assert_eq!(sum, (0..10_000u64).sum::<u64>());
+}
+
+#[test]
+fn runs_on_worker_threads() {
+    let pool = rayon::ThreadPoolBuilder::new().num_threads(4).build().unwrap();
+    let batch = batch(1_000);
+    let path = retrieve(Worker).mapped(Records);
+
+    let workers = pool.install(|| path.par_traverse(&batch).unwrap().collect::<Vec<_>>());
+    assert!(workers.iter().all(|w| w.as_ref().unwrap().is_some()));
+}
+
+#[test]
+fn errors() {
+    let job = Job { batch: batch(300) };
+    let path = retrieve_insecure(Checked).mapped(Records).from(BatchField);
+
+    let failed = path.par_traverse(&job).unwrap()
+        .enumerate()
+        .filter_map(|(i, r)| r.err().map(|e| e.with_index(i).to_string()))
+        .collect::<Vec<_>>();
+    assert_eq!(failed, vec!["at index 99: 99 is not allowed", "at index 199: 199 is not allowed", "at index 299: 299 is not allowed"]);
+}
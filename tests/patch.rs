@@ -0,0 +1,113 @@
+#![cfg(feature = "json")]
+
+extern crate attr;
+#[macro_use]
+extern crate serde_json;
+
+use attr::Traverse;
+use attr::ErrorKind;
+use attr::json::Pointer;
+use attr::patch::{diff, Operation, Patch};
+
+#[test]
+fn pointers() {
+    let doc = json!({ "a/b": { "m~n": [1, 2, 3] } });
+
+    let pointer = Pointer::parse("/a~1b/m~0n/1").unwrap();
+    assert_eq!(pointer.tokens(), &["a/b", "m~n", "1"]);
+    assert_eq!(pointer.to_string(), "/a~1b/m~0n/1");
+    assert_eq!(pointer.traverse(&doc).unwrap(), &json!(2));
+
+    assert_eq!(Pointer::parse("").unwrap().get(&doc).unwrap(), &doc);
+    assert!(Pointer::parse("a").is_err());
+    assert!(Pointer::parse("/a~2").is_err());
+
+    let missing = Pointer::parse("/a~1b/m~0n/7").unwrap().get(&doc).unwrap_err();
+    assert_eq!(missing.kind(), ErrorKind::Missing);
+    let leading_zero = Pointer::parse("/a~1b/m~0n/01").unwrap().get(&doc).unwrap_err();
+    assert_eq!(leading_zero.kind(), ErrorKind::Invalid);
+}
+
+#[test]
+fn apply_operations() {
+    let mut doc = json!({ "user": { "name": "Florian", "tags": ["rust"] }, "drafts": {} });
+
+    let patch: Patch = serde_json::from_value(json!([
+        { "op": "test", "path": "/user/name", "value": "Florian" },
+        { "op": "replace", "path": "/user/name", "value": "Flo" },
+        { "op": "add", "path": "/user/tags/-", "value": "berlin" },
+        { "op": "add", "path": "/user/tags/0", "value": "first" },
+        { "op": "copy", "from": "/user/name", "path": "/user/nickname" },
+        { "op": "move", "from": "/user/tags", "path": "/drafts/tags" },
+        { "op": "remove", "path": "/drafts/tags/1" }
+    ])).unwrap();
+
+    patch.apply(&mut doc).unwrap();
+
+    assert_eq!(doc, json!({
+        "user": { "name": "Flo", "nickname": "Flo" },
+        "drafts": { "tags": ["first", "berlin"] }
+    }));
+}
+
+#[test]
+fn failures_are_atomic_and_located() {
+    let original = json!({ "user": { "name": "Florian" } });
+    let mut doc = original.clone();
+
+    let patch = Patch(vec![
+        Operation::Replace { path: Pointer::parse("/user/name").unwrap(), value: json!("Flo") },
+        Operation::Remove { path: Pointer::parse("/user/email").unwrap() },
+    ]);
+
+    let error = patch.apply(&mut doc).unwrap_err();
+    assert_eq!(error.index(), Some(1));
+    assert!(error.is_missing());
+    assert_eq!(error.to_string(), "at index 1: remove /user/email: email is not present");
+    assert_eq!(doc, original);
+
+    let test = Patch(vec![Operation::Test { path: Pointer::parse("/user/name").unwrap(), value: json!("Flo") }]);
+    assert_eq!(test.apply(&mut doc).unwrap_err().kind(), ErrorKind::Invalid);
+
+    let into_itself = Patch(vec![Operation::Move { from: Pointer::parse("/user").unwrap(), path: Pointer::parse("/user/inner").unwrap() }]);
+    assert!(into_itself.apply(&mut doc).is_err());
+}
+
+#[test]
+fn generate_from_diff() {
+    let mut old = json!({ "name": "Florian", "tags": ["rust", "berlin", "cologne"], "age": 34 });
+    let new = json!({ "name": "Flo", "tags": ["rust"], "email": "flo@example.com" });
+
+    let patch = diff(&old, &new);
+    let rendered = patch.operations().iter().map(|o| o.to_string()).collect::<Vec<_>>();
+    assert_eq!(rendered, vec!["remove /age", "replace /name", "remove /tags/2", "remove /tags/1", "add /email"]);
+
+    let document = serde_json::to_value(&patch).unwrap();
+    assert_eq!(document[0], json!({ "op": "remove", "path": "/age" }));
+
+    patch.apply(&mut old).unwrap();
+    assert_eq!(old, new);
+    assert!(diff(&old, &new).is_empty());
+}
+
+#[test]
+fn test_compares_numbers_by_value() {
+    let mut doc = json!({ "count": 1, "ratio": 0.5, "items": [{ "n": 2.0 }], "big": u64::MAX });
+
+    let patch: Patch = serde_json::from_value(json!([
+        { "op": "test", "path": "/count", "value": 1.0 },
+        { "op": "test", "path": "/ratio", "value": 0.5 },
+        { "op": "test", "path": "/items", "value": [{ "n": 2 }] },
+        { "op": "test", "path": "/big", "value": u64::MAX }
+    ])).unwrap();
+    patch.apply(&mut doc).unwrap();
+
+    let mismatches = [json!({ "op": "test", "path": "/count", "value": 1.5 }),
+                      json!({ "op": "test", "path": "/count", "value": "1" }),
+                      json!({ "op": "test", "path": "/big", "value": u64::MAX - 1 }),
+                      json!({ "op": "test", "path": "/items", "value": [{ "n": 2, "m": 3 }] })];
+    for operation in mismatches.iter() {
+        let patch: Patch = serde_json::from_value(json!([operation])).unwrap();
+        assert!(patch.apply(&mut doc).is_err(), "{} should fail", operation);
+    }
+}
@@ -0,0 +1,54 @@
+#![cfg(feature = "json")]
+
+extern crate attr;
+#[macro_use]
+extern crate serde_json;
+
+use attr::Traverse;
+use attr::ErrorKind;
+use attr::json::Query;
+
+#[test]
+fn notations() {
+    let doc = json!({ "user": { "orders": [{ "id": 1 }, { "id": 2 }], "a.b": true } });
+
+    for query in &["user.orders[1].id", "/user/orders/1/id", "$.user.orders[1].id", "$['user'][\"orders\"][1]['id']", "user.orders.1.id"] {
+        let query = Query::parse(query).unwrap();
+        assert_eq!(query.select(&doc).unwrap(), vec![&json!(2)]);
+        assert!(query.is_single());
+    }
+
+    let ids = Query::parse("user.orders[*].id").unwrap();
+    assert_eq!(ids.traverse(&doc).unwrap(), vec![&json!(1), &json!(2)]);
+    assert_eq!(Query::parse("$.user.orders.*.id").unwrap(), ids);
+    assert!(!ids.is_single());
+
+    assert_eq!(Query::parse("$").unwrap().select(&doc).unwrap(), vec![&doc]);
+    assert_eq!(Query::parse("$.user['a.b']").unwrap().to_string(), "user['a.b']");
+    assert_eq!(Query::parse("/user/orders/0").unwrap().to_string(), "user.orders[0]");
+}
+
+#[test]
+fn parse_errors() {
+    for query in &["user..id", "user[", "user[x]", "user[01]", "$user", "$['user'", "$."] {
+        assert_eq!(Query::parse(query).unwrap_err().kind(), ErrorKind::Invalid, "{}", query);
+    }
+
+    assert_eq!(Query::parse("user..id").unwrap_err().message(), "unexpected character at position 5 in user..id");
+}
+
+#[test]
+fn failures_name_the_location() {
+    let doc = json!({ "user": { "orders": [{ "id": 1 }, { "total": 2 }], "name": "Flo" } });
+
+    let error = Query::parse("user.orders[*].id").unwrap().select(&doc).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::Missing);
+    assert_eq!(error.message(), "user.orders[1].id: id is not present");
+
+    let error = Query::parse("user.orders[5]").unwrap().select(&doc).unwrap_err();
+    assert_eq!(error.message(), "user.orders[5]: index 5 is out of bounds");
+
+    let error = Query::parse("user.name[*]").unwrap().select(&doc).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::Invalid);
+    assert_eq!(error.message(), "user.name.*: cannot iterate over a value that is neither object nor array");
+}
@@ -0,0 +1,220 @@
+extern crate attr;
+
+use attr::recursive;
+use attr::recursive_insecure;
+use attr::retrieve;
+use attr::Traverse;
+
+#[derive(Debug)]
+pub struct Node {
+    name: String,
+    children: Vec<Node>,
+}
+
+pub struct Tree {
+    root: Node,
+}
+
+/// A graph stored as an arena, which allows cycles.
+pub struct Graph {
+    nodes: Vec<Vertex>,
+}
+
+pub struct Vertex {
+    name: String,
+    edges: Vec<usize>,
+    parent: Option<usize>,
+}
+
+pub mod node {
+    use attr::Attr;
+    use attr::IterableAttr;
+
+    use super::Node;
+    use super::Tree;
+
+    pub struct Name;
+    pub struct Children;
+    pub struct Root;
+
+    impl<'a> Attr<&'a Node> for Name {
+        type Output = &'a str;
+
+        fn get(&self, i: &'a Node) -> &'a str {
+            i.name.as_ref()
+        }
+
+        fn name(&self) -> &'static str {
+            "name"
+        }
+    }
+
+    impl<'a> Attr<&'a Node> for Children {
+        type Output = &'a [Node];
+
+        fn get(&self, i: &'a Node) -> &'a [Node] {
+            i.children.as_ref()
+        }
+
+        fn name(&self) -> &'static str {
+            "children"
+        }
+    }
+
+    impl<'a> IterableAttr<'a, &'a Node> for Children {
+        type Item = &'a Node;
+
+        fn iter(&self, i: &'a Node) -> Box<dyn Iterator<Item=&'a Node> + 'a> {
+            Box::new(self.get(i).iter())
+        }
+    }
+
+    impl<'a> Attr<&'a Tree> for Root {
+        type Output = &'a Node;
+
+        fn get(&self, i: &'a Tree) -> &'a Node {
+            &i.root
+        }
+
+        fn name(&self) -> &'static str {
+            "root"
+        }
+    }
+}
+
+pub mod vertex {
+    use attr::Attr;
+    use attr::InsecureAttr;
+    use attr::IterableAttr;
+    use attr::Error;
+    use attr::Result;
+
+    use super::Graph;
+    use super::Vertex;
+
+    pub struct Edges<'g>(pub &'g Graph);
+    pub struct Parent<'g>(pub &'g Graph);
+
+    impl<'a, 'g: 'a> Attr<&'a Vertex> for Edges<'g> {
+        type Output = Vec<&'a Vertex>;
+
+        fn get(&self, i: &'a Vertex) -> Vec<&'a Vertex> {
+            i.edges.iter().map(|e| &self.0.nodes[*e]).collect()
+        }
+
+        fn name(&self) -> &'static str {
+            "edges"
+        }
+    }
+
+    impl<'a, 'g: 'a> IterableAttr<'a, &'a Vertex> for Edges<'g> {
+        type Item = &'a Vertex;
+
+        fn iter(&self, i: &'a Vertex) -> Box<dyn Iterator<Item=&'a Vertex> + 'a> {
+            Box::new(self.get(i).into_iter())
+        }
+    }
+
+    impl<'a, 'g: 'a> InsecureAttr<&'a Vertex> for Parent<'g> {
+        type Output = &'a Vertex;
+
+        fn get(&self, i: &'a Vertex) -> Result<&'a Vertex> {
+            i.parent.map(|p| &self.0.nodes[p]).ok_or_else(|| Error::missing(format!("{} has no parent", i.name)))
+        }
+
+        fn name(&self) -> &'static str {
+            "parent"
+        }
+    }
+}
+
+fn leaf(name: &str) -> Node {
+    Node { name: name.into(), children: vec![] }
+}
+
+fn tree() -> Tree {
+    Tree {
+        root: Node {
+            name: "a".into(),
+            children: vec![
+                Node { name: "b".into(), children: vec![leaf("d"), leaf("e")] },
+                Node { name: "c".into(), children: vec![leaf("f")] },
+            ]
+        }
+    }
+}
+
+fn names<'a, I: Iterator<Item=attr::Result<&'a Node>>>(iter: I) -> Vec<&'a str> {
+    iter.map(|n| n.unwrap().name.as_ref()).collect()
+}
+
+#[test]
+fn depth_first() {
+    let tree = tree();
+    let path = recursive(node::Children);
+
+    let result = names(path.traverse(&tree.root).unwrap());
+    assert_eq!(result, vec!["a", "b", "d", "e", "c", "f"]);
+}
+
+#[test]
+fn breadth_first() {
+    let tree = tree();
+    let path = recursive(node::Children).breadth_first();
+
+    let result = names(path.traverse(&tree.root).unwrap());
+    assert_eq!(result, vec!["a", "b", "c", "d", "e", "f"]);
+}
+
+#[test]
+fn max_depth() {
+    let tree = tree();
+    let path = recursive(node::Children).breadth_first().max_depth(1);
+
+    let result = names(path.traverse(&tree.root).unwrap());
+    assert_eq!(result, vec!["a", "b", "c"]);
+}
+
+#[test]
+fn from_attribute() {
+    let tree = tree();
+    let path = recursive(node::Children).max_depth(1).from(node::Root);
+    assert_eq!(path.to_string(), "root.children[*]");
+
+    let result = names(path.traverse(&tree).unwrap());
+    assert_eq!(result, vec!["a", "b", "c"]);
+
+    let root = retrieve(node::Name).from(node::Root);
+    assert_eq!(root.traverse(&tree).unwrap(), "a");
+}
+
+#[test]
+fn cycles_terminate() {
+    let graph = Graph {
+        nodes: vec![
+            Vertex { name: "x".into(), edges: vec![1], parent: None },
+            Vertex { name: "y".into(), edges: vec![2, 0], parent: Some(0) },
+            Vertex { name: "z".into(), edges: vec![0, 1], parent: Some(1) },
+        ]
+    };
+
+    let path = recursive(vertex::Edges(&graph));
+    let result = path.traverse(&graph.nodes[0]).unwrap().map(|v| v.unwrap().name.as_ref()).collect::<Vec<&str>>();
+    assert_eq!(result, vec!["x", "y", "z"]);
+}
+
+#[test]
+fn follow_links() {
+    let graph = Graph {
+        nodes: vec![
+            Vertex { name: "ceo".into(), edges: vec![], parent: None },
+            Vertex { name: "cto".into(), edges: vec![], parent: Some(0) },
+            Vertex { name: "dev".into(), edges: vec![], parent: Some(1) },
+        ]
+    };
+
+    let path = recursive_insecure(vertex::Parent(&graph));
+    assert_eq!(path.to_string(), "parent[*]");
+    let result = path.traverse(&graph.nodes[2]).unwrap().map(|v| v.unwrap().name.as_ref()).collect::<Vec<&str>>();
+    assert_eq!(result, vec!["dev", "cto", "ceo"]);
+}
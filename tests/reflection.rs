@@ -0,0 +1,162 @@
+#![cfg(feature = "serde")]
+
+extern crate attr;
+extern crate serde;
+extern crate serde_json;
+
+use std::collections::BTreeMap;
+
+use serde::{Serialize, Serializer};
+
+use attr::retrieve_insecure;
+use attr::{Attr, InsecureAttr, Traverse};
+use attr::ErrorKind;
+use attr::{DynValue, Value};
+use attr::value::{to_value, Field, Key, Index};
+
+#[derive(Serialize)]
+struct User {
+    name: String,
+    data: Data,
+    orders: Vec<Order>,
+    role: Role,
+}
+
+#[derive(Serialize)]
+struct Data {
+    email: Option<String>,
+}
+
+#[derive(Serialize)]
+struct Order {
+    id: u32,
+    total: f64,
+}
+
+#[derive(Serialize)]
+enum Role {
+    Admin,
+    #[allow(dead_code)]
+    Member { since: u16 },
+}
+
+fn user() -> User {
+    User {
+        name: "flo".to_owned(),
+        data: Data { email: Some("flo@example.com".to_owned()) },
+        orders: vec![Order { id: 1, total: 9.5 }, Order { id: 2, total: 20.0 }],
+        role: Role::Admin,
+    }
+}
+
+#[test]
+fn paths_over_converted_values() {
+    let value = to_value(&user()).unwrap();
+
+    let email = retrieve_insecure(Key::new("email")).try(Key::new("data"));
+    assert_eq!(email.traverse(&value).unwrap(), &Value::from("flo@example.com"));
+
+    let total = retrieve_insecure(Key::new("total")).try(Index::new(1)).try(Key::new("orders"));
+    assert_eq!(total.traverse(&value).unwrap(), &Value::F64(20.0));
+
+    assert_eq!(retrieve_insecure(Key::new("role")).traverse(&value).unwrap(), &Value::from("Admin"));
+
+    let missing = retrieve_insecure(Key::new("phone")).try(Key::new("data")).traverse(&value).unwrap_err();
+    assert_eq!(missing.message(), "phone is not present");
+}
+
+#[test]
+fn data_model() {
+    assert_eq!(to_value(&None::<u8>).unwrap(), Value::Null);
+    assert_eq!(to_value(&(1i8, 'x')).unwrap(), Value::List(vec![Value::I64(1), Value::from("x")]));
+
+    let member = to_value(&Role::Member { since: 2019 }).unwrap();
+    let since = retrieve_insecure(Key::new("since")).try(Key::new("Member"));
+    assert_eq!(since.traverse(&member).unwrap(), &Value::U64(2019));
+
+    let mut ports = BTreeMap::new();
+    ports.insert(80u16, "http");
+    let ports = to_value(&ports).unwrap();
+    assert_eq!(retrieve_insecure(Key::new("80")).traverse(&ports).unwrap(), &Value::from("http"));
+
+    let mut invalid = BTreeMap::new();
+    invalid.insert(vec![1u8], 1u8);
+    assert_eq!(to_value(&invalid).unwrap_err().kind(), ErrorKind::Invalid);
+    assert!(to_value(&u128::MAX).is_err());
+}
+
+#[test]
+fn serde_round_trip() {
+    let value = to_value(&user()).unwrap();
+
+    let json = serde_json::to_string(&value).unwrap();
+    assert_eq!(json, r#"{"data":{"email":"flo@example.com"},"name":"flo","orders":[{"id":1,"total":9.5},{"id":2,"total":20.0}],"role":"Admin"}"#);
+
+    let restored: Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(restored, value);
+}
+
+pub struct DataAttribute;
+
+impl<'a> Attr<&'a User> for DataAttribute {
+    type Output = &'a Data;
+
+    fn name(&self) -> &str {
+        "data"
+    }
+
+    fn get(&self, u: &'a User) -> &'a Data {
+        &u.data
+    }
+}
+
+/// Fails whenever it is serialized.
+struct Unserializable;
+
+impl Serialize for Unserializable {
+    fn serialize<S: Serializer>(&self, _serializer: S) -> Result<S::Ok, S::Error> {
+        Err(serde::ser::Error::custom("not serializable"))
+    }
+}
+
+#[test]
+fn fields() {
+    let user = user();
+
+    assert_eq!(Field::new("data.email").get(&user).unwrap(), Value::from("flo@example.com"));
+    assert_eq!(Field::new("orders.1.total").get(&user).unwrap(), Value::F64(20.0));
+    assert_eq!(Field::new("orders.0").get(&user).unwrap(), to_value(&user.orders[0]).unwrap());
+    assert_eq!(Field::new("").get(&user).unwrap(), to_value(&user).unwrap());
+    let member = Role::Member { since: 2019 };
+    assert_eq!(Field::new("Member.since").get(&member).unwrap(), Value::U64(2019));
+    assert_eq!(Field::new("Member").get(&member).unwrap(), to_value(&member).unwrap().get_key("Member").cloned().unwrap());
+
+    let email = retrieve_insecure(Field::new("email")).from(DataAttribute);
+    assert_eq!(email.traverse(&user).unwrap(), Value::from("flo@example.com"));
+
+    assert_eq!(Field::new("data.phone").get(&user).unwrap_err().message(), "phone is not present");
+    assert_eq!(Field::new("orders.2").get(&user).unwrap_err().message(), "index 2 is out of bounds");
+    assert_eq!(Field::new("name.first").get(&user).unwrap_err().kind(), ErrorKind::Invalid);
+    assert_eq!(Field::new("role.since").get(&user).unwrap_err().kind(), ErrorKind::Invalid);
+}
+
+#[test]
+fn fields_skip_other_members() {
+    let mut pair = BTreeMap::new();
+    pair.insert("broken", (Unserializable, 1u8));
+    pair.insert("fine", (Unserializable, 2u8));
+
+    assert_eq!(Field::new("fine.1").get(&pair).unwrap(), Value::U64(2));
+    assert!(to_value(&pair).is_err());
+    assert_eq!(Field::new("fine.0").get(&pair).unwrap_err().message(), "not serializable");
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn pointers() {
+    use attr::json::Pointer;
+
+    let value = to_value(&user()).unwrap();
+    let pointer = Pointer::parse("/orders/0/id").unwrap();
+    assert_eq!(pointer.traverse(&value).unwrap(), &Value::U64(1));
+}
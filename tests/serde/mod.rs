@@ -1,6 +1,7 @@
 use serde_json::value::Value;
 use attr::InsecureAttr;
 use attr::InsecureIndexableAttr;
+use attr::Error;
 use attr::Result;
 
 pub struct SerdeAttribute<'a> {
@@ -9,7 +10,7 @@ pub struct SerdeAttribute<'a> {
 
 impl<'a> SerdeAttribute<'a> {
     pub fn new(name: &'a str) -> SerdeAttribute<'a> {
-        SerdeAttribute { name: name }
+        SerdeAttribute { name }
     }
 }
 
@@ -22,8 +23,8 @@ impl<'a, 'b: 'a> InsecureAttr<&'a Value> for SerdeAttribute<'b> {
 
     fn get(&self, i: &'a Value) -> Result<&'a Value> {
         match *i {
-            Value::Object(ref m) => { m.get(self.name).ok_or_else(|| {format!("{} it empty or not present", self.name)}) },
-            _ => Err(format!("{} is not an non-object", self.name))
+            Value::Object(ref m) => { m.get(self.name).ok_or_else(|| Error::missing(format!("{} it empty or not present", self.name))) },
+            _ => Err(Error::invalid(format!("{} is not an non-object", self.name)))
         }
     }
 }
@@ -37,8 +38,8 @@ impl<'a, 'b: 'a> InsecureAttr<&'a mut Value> for SerdeAttribute<'b> {
 
     fn get(&self, i: &'a mut Value) -> Result<&'a mut Value> {
         match *i {
-            Value::Object(ref mut m) => { m.get_mut(self.name).ok_or_else(|| { format!("{} it empty or not present", self.name)}) },
-            _ => Err(format!("{} is not an non-object", self.name))
+            Value::Object(ref mut m) => { m.get_mut(self.name).ok_or_else(|| Error::missing(format!("{} it empty or not present", self.name))) },
+            _ => Err(Error::invalid(format!("{} is not an non-object", self.name)))
         }
     }
 }
@@ -49,13 +50,13 @@ impl<'a, 'b: 'a> InsecureIndexableAttr<&'a Value, usize> for SerdeAttribute<'b>
     fn at(&self, i: &'a Value, idx: usize) -> Result<&'a Value> {
         let v = self.get(i);
         match v {
-            Ok(&Value::Array(ref vec)) => { Ok(& vec[idx]) },
+            Ok(Value::Array(vec)) => { Ok(& vec[idx]) },
             _ => Err("Not an object or array".into())
         }
     }
 }
 
-impl<'a, 'b : 'a> InsecureIndexableAttr<&'a mut Value, usize> for SerdeAttribute<'a> {
+impl<'a> InsecureIndexableAttr<&'a mut Value, usize> for SerdeAttribute<'a> {
     type Output = &'a mut Value;
 
     fn at(&self, i: &'a mut Value, idx: usize) -> Result<&'a mut Value> {
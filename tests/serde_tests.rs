@@ -22,8 +22,8 @@ fn test_attr() {
     let attr_x = SerdeAttribute::new("x");
     let attr_y = SerdeAttribute::new("y");
 
-    assert_eq!(attr_x.get(&obj).unwrap(), &Value::U64(1));
-    assert_eq!(attr_y.at(&obj, 1).unwrap(), &Value::U64(2));
+    assert_eq!(attr_x.get(&obj).unwrap(), &Value::from(1));
+    assert_eq!(attr_y.at(&obj, 1).unwrap(), &Value::from(2));
 }
 
 #[test]
@@ -35,7 +35,7 @@ fn test_multiple_attr() {
 
     let path = retrieve_insecure(attr_z).try(attr_y);
 
-    assert_eq!(path.traverse(&obj), Ok(&Value::U64(1)));
+    assert_eq!(path.traverse(&obj), Ok(&Value::from(1)));
 }
 
 struct Foo {
@@ -67,5 +67,5 @@ fn test_combine() {
 
     let path = retrieve_insecure(attr).from(Inner);
 
-    assert_eq!(path.traverse(&obj), Ok(&Value::U64(1)));
+    assert_eq!(path.traverse(&obj), Ok(&Value::from(1)));
 }
@@ -105,6 +105,6 @@ fn simple_access() {
 
     let mut f = Bar { batz: "foobar".into() };
 
-    let mut batz = Bar::attrs().batz.get(&mut f);
+    let batz = Bar::attrs().batz.get(&mut f);
     batz.push('b');
 }
@@ -0,0 +1,201 @@
+extern crate attr;
+
+use attr::Registry;
+use attr::ErrorKind;
+use attr::template::Template;
+
+pub struct User {
+    data: Data,
+    orders: Vec<Order>,
+}
+
+pub struct Data {
+    email: String,
+    nickname: Option<String>,
+}
+
+pub struct Order {
+    title: String,
+    total: u32,
+    tags: Vec<String>,
+}
+
+pub mod user {
+    use attr::{Attr, InsecureAttr, IterableAttr, Error, Result};
+
+    use super::{User, Data, Order};
+
+    pub struct DataField;
+    pub struct Email;
+    pub struct Nickname;
+    pub struct Orders;
+    pub struct Title;
+    pub struct Total;
+    pub struct Tags;
+
+    impl<'a> Attr<&'a User> for DataField {
+        type Output = &'a Data;
+
+        fn get(&self, i: &'a User) -> &'a Data {
+            &i.data
+        }
+
+        fn name(&self) -> &'static str {
+            "data"
+        }
+    }
+
+    impl<'a> Attr<&'a Data> for Email {
+        type Output = &'a str;
+
+        fn get(&self, i: &'a Data) -> &'a str {
+            &i.email
+        }
+
+        fn name(&self) -> &'static str {
+            "email"
+        }
+    }
+
+    impl<'a> InsecureAttr<&'a Data> for Nickname {
+        type Output = &'a str;
+
+        fn get(&self, i: &'a Data) -> Result<&'a str> {
+            i.nickname.as_deref().ok_or_else(|| Error::missing("no nickname set"))
+        }
+
+        fn name(&self) -> &'static str {
+            "nickname"
+        }
+    }
+
+    impl<'a> Attr<&'a User> for Orders {
+        type Output = &'a [Order];
+
+        fn get(&self, i: &'a User) -> &'a [Order] {
+            &i.orders
+        }
+
+        fn name(&self) -> &'static str {
+            "orders"
+        }
+    }
+
+    impl<'a> IterableAttr<'a, &'a User> for Orders {
+        type Item = &'a Order;
+
+        fn iter(&self, i: &'a User) -> Box<dyn Iterator<Item=&'a Order> + 'a> {
+            Box::new(i.orders.iter())
+        }
+    }
+
+    impl<'a> Attr<&'a Order> for Title {
+        type Output = &'a str;
+
+        fn get(&self, i: &'a Order) -> &'a str {
+            &i.title
+        }
+
+        fn name(&self) -> &'static str {
+            "title"
+        }
+    }
+
+    impl<'a> Attr<&'a Order> for Total {
+        type Output = u32;
+
+        fn get(&self, i: &'a Order) -> u32 {
+            i.total
+        }
+
+        fn name(&self) -> &'static str {
+            "total"
+        }
+    }
+
+    impl<'a> Attr<&'a Order> for Tags {
+        type Output = &'a [String];
+
+        fn get(&self, i: &'a Order) -> &'a [String] {
+            &i.tags
+        }
+
+        fn name(&self) -> &'static str {
+            "tags"
+        }
+    }
+
+    impl<'a> IterableAttr<'a, &'a Order> for Tags {
+        type Item = &'a String;
+
+        fn iter(&self, i: &'a Order) -> Box<dyn Iterator<Item=&'a String> + 'a> {
+            Box::new(i.tags.iter())
+        }
+    }
+}
+
+fn registry() -> Registry {
+    let mut registry = Registry::new();
+    registry.register(user::DataField)
+        .register(user::Email)
+        .register_insecure(user::Nickname)
+        .register_iterable(user::Orders)
+        .register(user::Title)
+        .register(user::Total)
+        .register_iterable(user::Tags);
+    registry
+}
+
+fn user(nickname: Option<&str>) -> User {
+    User {
+        data: Data { email: "flo@example.com".to_string(), nickname: nickname.map(|n| n.to_string()) },
+        orders: vec![
+            Order { title: "Book".to_string(), total: 12, tags: vec!["paper".to_string(), "gift".to_string()] },
+            Order { title: "Lamp".to_string(), total: 30, tags: vec![] },
+        ],
+    }
+}
+
+#[test]
+fn renders_placeholders_and_blocks() {
+    let template = Template::parse("Hi {{ data.nickname }} <{{data.email}}>,
+{{#each orders}}* {{title}} ({{total}} EUR){{#each tags}} #{{this}}{{/each}}
+{{/each}}Bye", &registry()).unwrap();
+
+    assert_eq!(template.render(&user(Some("Flo"))).unwrap(), "Hi Flo <flo@example.com>,
+* Book (12 EUR) #paper #gift
+* Lamp (30 EUR)
+Bye");
+}
+
+#[test]
+fn parse_errors() {
+    let registry = registry();
+    let error = |source: &str| Template::parse(source, &registry).err().unwrap().to_string();
+
+    assert_eq!(error("Hi {{data.email"), "line 1, column 4: {{ is not closed");
+    assert_eq!(error("Hi\n  {{data.mail}}"), "line 2, column 3: data.mail failed at mail: no attribute mail is registered");
+    assert_eq!(error("{{#each orders}}\n{{title}}"), "line 1, column 1: {{#each orders}} is not closed");
+    assert_eq!(error("{{title}}{{/each}}"), "line 1, column 10: {{/each}} without {{#each}}");
+    assert_eq!(error("{{#if orders}}{{/if}}"), "line 1, column 1: unknown block {{#if orders}}");
+    assert_eq!(error("{{data..email}}"), "line 1, column 1: data..email is not a valid path");
+}
+
+#[test]
+fn render_errors() {
+    let registry = registry();
+
+    let template = Template::parse("Dear\n {{data.nickname}}", &registry).unwrap();
+    let error = template.render(&user(None)).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::Missing);
+    assert_eq!(error.message(), "line 2, column 2: data.nickname failed at nickname: no nickname set");
+
+    let template = Template::parse("{{orders.title}}", &registry).unwrap();
+    assert_eq!(template.render(&user(None)).unwrap_err().message(), "line 1, column 1: orders.title leads to 2 values instead of one");
+
+    let template = Template::parse("{{data}}", &registry).unwrap();
+    assert_eq!(template.render(&user(None)).unwrap_err().message(), "line 1, column 1: data cannot be rendered");
+
+    let template = Template::parse("{{title}}", &registry).unwrap();
+    assert_eq!(template.render(&user(None)).unwrap_err().message(), "line 1, column 1: title failed at title: title is not an attribute of the value at hand");
+}
@@ -0,0 +1,74 @@
+#![cfg(feature = "toml")]
+
+extern crate attr;
+extern crate toml;
+
+use toml::Value;
+
+use attr::retrieve_insecure;
+use attr::Traverse;
+use attr::ErrorKind;
+use attr::{InsecureIndexableAttr, InsecureIterableAttr};
+use attr::toml::{Key, Each};
+
+fn config() -> Value {
+    r#"
+    [data]
+    email = "flo@example.com"
+    ports = [80, 443]
+
+    [[users]]
+    name = "flo"
+
+    [[users]]
+    name = "skade"
+    "#.parse().unwrap()
+}
+
+#[test]
+fn paths() {
+    let config = config();
+
+    let email = retrieve_insecure(Key::new("email")).try(Key::new("data"));
+    assert_eq!(email.traverse(&config).unwrap().as_str(), Some("flo@example.com"));
+
+    let name = retrieve_insecure(Key::new("name")).try(Key::new("1")).try(Key::new("users"));
+    assert_eq!(name.traverse(&config).unwrap().as_str(), Some("skade"));
+
+    let mut config = config;
+    *retrieve_insecure(Key::new("email")).try(Key::new("data")).traverse(&mut config).unwrap() = Value::from("b@example.com");
+    assert_eq!(config["data"]["email"].as_str(), Some("b@example.com"));
+}
+
+#[test]
+fn indexing_and_iteration() {
+    let config = config();
+    let data = &config["data"];
+
+    assert_eq!(Key::new("ports").at(data, 1).unwrap().as_integer(), Some(443));
+    assert!(Key::new("ports").at(data, 2).unwrap_err().is_missing());
+    assert_eq!(Key::new("email").at(data, 0).unwrap_err().kind(), ErrorKind::Invalid);
+
+    let names = Each.iter(&config["users"]).unwrap().map(|u| u["name"].as_str().unwrap()).collect::<Vec<_>>();
+    assert_eq!(names, vec!["flo", "skade"]);
+    assert_eq!(Each.iter(data).unwrap().count(), 2);
+    assert!(Each.iter(&data["email"]).is_err());
+}
+
+#[test]
+fn errors_mirror_json() {
+    let config = config();
+
+    let missing = retrieve_insecure(Key::new("phone")).try(Key::new("data")).traverse(&config).unwrap_err();
+    assert_eq!(missing.kind(), ErrorKind::Missing);
+    assert_eq!(missing.message(), "phone is not present");
+
+    let out_of_bounds = retrieve_insecure(Key::new("5")).try(Key::new("users")).traverse(&config).unwrap_err();
+    assert_eq!(out_of_bounds.message(), "index 5 is out of bounds");
+
+    let leading_zero = retrieve_insecure(Key::new("01")).try(Key::new("users")).traverse(&config).unwrap_err();
+    assert_eq!(leading_zero.kind(), ErrorKind::Invalid);
+
+    let scalar = retrieve_insecure(Key::new("x")).try(Key::new("email")).try(Key::new("data")).traverse(&config).unwrap_err();
+    assert_eq!(scalar.kind(), ErrorKind::Invalid);
+}
@@ -0,0 +1,223 @@
+extern crate attr;
+
+use attr::retrieve;
+use attr::retrieve_insecure;
+use attr::validate::*;
+
+pub struct User {
+    name: String,
+    age: u32,
+    role: String,
+    nickname: Option<String>,
+    tags: Vec<Tag>,
+}
+
+pub struct Tag {
+    label: String,
+}
+
+pub mod user {
+    use attr::Attr;
+    use attr::InsecureAttr;
+    use attr::IterableAttr;
+    use attr::Error;
+    use attr::Result;
+
+    use super::Tag;
+    use super::User;
+
+    pub struct Name;
+    pub struct Age;
+    pub struct Role;
+    pub struct Nickname;
+    pub struct Tags;
+    pub struct Label;
+
+    impl<'a> Attr<&'a User> for Name {
+        type Output = &'a str;
+
+        fn get(&self, i: &'a User) -> &'a str {
+            i.name.as_ref()
+        }
+
+        fn name(&self) -> &'static str {
+            "name"
+        }
+    }
+
+    impl<'a> Attr<&'a User> for Age {
+        type Output = u32;
+
+        fn get(&self, i: &'a User) -> u32 {
+            i.age
+        }
+
+        fn name(&self) -> &'static str {
+            "age"
+        }
+    }
+
+    impl<'a> Attr<&'a User> for Role {
+        type Output = &'a str;
+
+        fn get(&self, i: &'a User) -> &'a str {
+            i.role.as_ref()
+        }
+
+        fn name(&self) -> &'static str {
+            "role"
+        }
+    }
+
+    impl<'a> InsecureAttr<&'a User> for Nickname {
+        type Output = &'a str;
+
+        fn get(&self, i: &'a User) -> Result<&'a str> {
+            i.nickname.as_ref().map(|n| n.as_ref()).ok_or_else(|| Error::missing("nickname is not set"))
+        }
+
+        fn name(&self) -> &'static str {
+            "nickname"
+        }
+    }
+
+    impl<'a> Attr<&'a User> for Tags {
+        type Output = &'a [Tag];
+
+        fn get(&self, i: &'a User) -> &'a [Tag] {
+            i.tags.as_ref()
+        }
+
+        fn name(&self) -> &'static str {
+            "tags"
+        }
+    }
+
+    impl<'a> IterableAttr<'a, &'a User> for Tags {
+        type Item = &'a Tag;
+
+        fn iter(&self, i: &'a User) -> Box<dyn Iterator<Item=&'a Tag> + 'a> {
+            Box::new(self.get(i).iter())
+        }
+    }
+
+    impl<'a> Attr<&'a Tag> for Label {
+        type Output = &'a str;
+
+        fn get(&self, i: &'a Tag) -> &'a str {
+            i.label.as_ref()
+        }
+
+        fn name(&self) -> &'static str {
+            "label"
+        }
+    }
+}
+
+fn tag(label: &str) -> Tag {
+    Tag { label: label.into() }
+}
+
+fn user() -> User {
+    User {
+        name: "Florian".into(),
+        age: 34,
+        role: "admin".into(),
+        nickname: None,
+        tags: vec![tag("rust"), tag("berlin")],
+    }
+}
+
+#[test]
+fn valid_user() {
+    let user = user();
+
+    let rules = (
+        rule(retrieve(user::Name), required().and(length(1, 20)).and(pattern("F*"))),
+        rule(retrieve(user::Age), range(18, 130)),
+        rule(retrieve(user::Role), one_of(vec!["admin", "user"])),
+        rule(retrieve_insecure(user::Nickname), length(3, 10)),
+        each(retrieve(user::Label).mapped(user::Tags), length(1, 10)),
+    );
+
+    let report = rules.validate(&user);
+    assert!(report.is_valid(), "{}", report);
+}
+
+#[test]
+fn collects_all_failures() {
+    let mut user = user();
+    user.age = 12;
+    user.role = "root".into();
+    user.tags.push(tag("a-very-long-tag"));
+
+    let rules = (
+        rule(retrieve(user::Age), range(18, 130)),
+        rule(retrieve(user::Role), one_of(vec!["admin", "user"])),
+        rule(retrieve_insecure(user::Nickname), required()),
+        each(retrieve(user::Label).mapped(user::Tags), length(1, 10)),
+    );
+
+    let report = rules.validate(&user);
+    let paths = report.failures().iter().map(|f| f.path.as_ref()).collect::<Vec<&str>>();
+
+    assert_eq!(paths, vec!["age", "role", "nickname", "tags[2].label"]);
+    assert_eq!(report.failures()[0].message, "12 is not between 18 and 130");
+    assert_eq!(report.failures()[2].message, "is required");
+    assert!(report.into_result().is_err());
+}
+
+#[test]
+fn composition() {
+    let user = user();
+
+    let admin_or_short = one_of(vec!["admin"]).or(length(1, 3));
+    let check = custom(|name: &&str| if name.contains(' ') { Err("contains spaces".into()) } else { Ok(()) });
+
+    let rules = (
+        rule(retrieve(user::Role), admin_or_short),
+        rule(retrieve(user::Name), check.and(pattern("*?n"))),
+        rule(retrieve(user::Name), pattern("*z*").or(length(10, 20))),
+    );
+
+    let report = rules.validate(&user);
+    assert_eq!(report.failures().len(), 1);
+    assert_eq!(report.to_string(), "name: does not match *z* or length 7 is not between 10 and 20");
+}
+
+#[test]
+fn rule_composition() {
+    let user = user();
+
+    let nickname_or_name = rule(retrieve_insecure(user::Nickname), required()).or(rule(retrieve(user::Name), length(1, 5)));
+    let report = nickname_or_name.validate(&user);
+    assert_eq!(report.to_string(), "nickname: is required\nname: length 7 is not between 1 and 5");
+
+    let admin_or_adult = rule(retrieve(user::Role), one_of(vec!["admin"])).or(rule(retrieve(user::Age), range(18, 130)));
+    assert!(admin_or_adult.validate(&user).is_valid());
+
+    let rules = rule(retrieve(user::Name), pattern("*z*")).and(each(retrieve(user::Label).mapped(user::Tags), length(5, 10)));
+    let report = rules.validate(&user);
+    assert_eq!(report.failures().len(), 2);
+    assert_eq!(report.failures()[1].path, "tags[0].label");
+
+    let nested = (rule(retrieve(user::Age), range(40, 50)), rule(retrieve(user::Name), required())).or(admin_or_adult);
+    assert!(nested.validate(&user).is_valid());
+}
+
+#[test]
+fn patterns() {
+    let matching = [("F*", "Florian"), ("*?n", "Florian"), ("F?o*a*", "Florian"), ("*", ""), ("**", "a"), ("a*b*c", "abbbc")];
+    for &(p, value) in matching.iter() {
+        assert_eq!(pattern(p).validate(&value), Ok(()), "{} should match {}", p, value);
+    }
+
+    let failing = [("F?", "Florian"), ("*z*", "Florian"), ("?", ""), ("a*b*c", "abbbcd")];
+    for &(p, value) in failing.iter() {
+        assert!(pattern(p).validate(&value).is_err(), "{} should not match {}", p, value);
+    }
+
+    // Exponential for naive backtracking over every `*`.
+    let value = "a".repeat(10_000);
+    assert!(pattern("*a*a*a*a*a*a*a*a*b").validate(&value).is_err());
+}
@@ -0,0 +1,91 @@
+extern crate attr;
+
+use std::collections::BTreeMap;
+
+use attr::retrieve_insecure;
+use attr::Traverse;
+use attr::ErrorKind;
+use attr::{IterableAttr, InsecureIterableAttr, InsecureIndexableAttr};
+use attr::{DynValue, Value};
+use attr::value::{Key, Index, Each, Kind};
+
+fn map(members: Vec<(&str, Value)>) -> Value {
+    Value::Map(members.into_iter().map(|(k, v)| (k.to_owned(), v)).collect::<BTreeMap<_, _>>())
+}
+
+fn user() -> Value {
+    map(vec![
+        ("data", map(vec![("email", Value::from("flo@example.com"))])),
+        ("ports", Value::List(vec![Value::from(80u16), Value::from(443u16)])),
+        ("admin", Value::from(true)),
+    ])
+}
+
+#[test]
+fn paths() {
+    let user = user();
+
+    let email = retrieve_insecure(Key::new("email")).try(Key::new("data"));
+    assert_eq!(email.traverse(&user).unwrap(), &Value::from("flo@example.com"));
+
+    let owned = String::from("flo@example.com");
+    assert_eq!(email.traverse(&user).unwrap(), &Value::from(owned.as_str()));
+
+    let port = retrieve_insecure(Index::new(1)).try(Key::new("ports"));
+    assert_eq!(port.traverse(&user).unwrap(), &Value::U64(443));
+    assert_eq!(Key::new("ports").at(&user, 0).unwrap(), &Value::U64(80));
+
+    let mut user = user;
+    *retrieve_insecure(Key::new("0")).try(Key::new("ports")).traverse(&mut user).unwrap() = Value::from(8080u16);
+    assert_eq!(user.get_key("ports").and_then(|p| p.get_index(0)), Some(&Value::U64(8080)));
+}
+
+#[test]
+fn errors() {
+    let user = user();
+
+    let missing = retrieve_insecure(Key::new("phone")).try(Key::new("data")).traverse(&user).unwrap_err();
+    assert_eq!(missing.kind(), ErrorKind::Missing);
+    assert_eq!(missing.message(), "phone is not present");
+
+    let out_of_bounds = retrieve_insecure(Index::new(2)).try(Key::new("ports")).traverse(&user).unwrap_err();
+    assert_eq!(out_of_bounds.message(), "index 2 is out of bounds");
+
+    let scalar = retrieve_insecure(Key::new("x")).try(Key::new("admin")).traverse(&user).unwrap_err();
+    assert_eq!(scalar.kind(), ErrorKind::Invalid);
+    assert_eq!(scalar.message(), "cannot retrieve x from a value that is neither map nor list");
+
+    assert_eq!(Key::new("data").at(&user, 0).unwrap_err().message(), "data is not a list");
+}
+
+#[test]
+fn iteration() {
+    let user = user();
+
+    assert_eq!(user.kind(), Kind::Map);
+    assert_eq!(InsecureIterableAttr::iter(&Each, &user).unwrap().count(), 3);
+
+    let ports = user.get_key("ports").unwrap();
+    let ports = IterableAttr::iter(&Each, ports).collect::<Vec<_>>();
+    assert_eq!(ports, vec![&Value::U64(80), &Value::U64(443)]);
+
+    let admin = user.get_key("admin").unwrap();
+    assert_eq!(IterableAttr::iter(&Each, admin).count(), 0);
+    assert!(InsecureIterableAttr::iter(&Each, admin).is_err());
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn json_values() {
+    use attr::InsecureAttr;
+    use attr::json;
+
+    let user = serde_json::json!({ "orders": [{ "id": 1 }, { "id": 2 }] });
+
+    let orders = retrieve_insecure(Key::new("orders"));
+    let ids = InsecureIterableAttr::iter(&Each, orders.traverse(&user).unwrap()).unwrap()
+        .map(|o| json::Key::new("id").get(o).unwrap().as_u64().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(ids, vec![1, 2]);
+    assert_eq!(user.kind(), Kind::Map);
+}
@@ -0,0 +1,169 @@
+#[macro_use]
+extern crate attr;
+
+use attr::retrieve;
+use attr::retrieve_insecure;
+use attr::zip;
+use attr::Traverse;
+
+pub struct User {
+    name: String,
+    age: u32,
+    nickname: Option<String>,
+    data: Data,
+}
+
+pub struct Data {
+    email: String,
+}
+
+pub mod user {
+    use attr::Attr;
+    use attr::InsecureAttr;
+    use attr::Error;
+    use attr::Result;
+
+    use super::User;
+    use super::Data;
+
+    pub struct Name;
+    pub struct Age;
+    pub struct Nickname;
+    pub struct DataField;
+    pub struct Email;
+
+    impl<'a> Attr<&'a User> for Name {
+        type Output = &'a str;
+
+        fn get(&self, i: &'a User) -> &'a str {
+            i.name.as_ref()
+        }
+
+        fn name(&self) -> &'static str {
+            "name"
+        }
+    }
+
+    impl<'a> Attr<&'a User> for Age {
+        type Output = u32;
+
+        fn get(&self, i: &'a User) -> u32 {
+            i.age
+        }
+
+        fn name(&self) -> &'static str {
+            "age"
+        }
+    }
+
+    impl<'a> InsecureAttr<&'a User> for Nickname {
+        type Output = &'a str;
+
+        fn get(&self, i: &'a User) -> Result<&'a str> {
+            i.nickname.as_ref().map(|n| n.as_ref()).ok_or_else(|| Error::missing("nickname is not set"))
+        }
+
+        fn name(&self) -> &'static str {
+            "nickname"
+        }
+    }
+
+    impl<'a> Attr<&'a User> for DataField {
+        type Output = &'a Data;
+
+        fn get(&self, i: &'a User) -> &'a Data {
+            &i.data
+        }
+
+        fn name(&self) -> &'static str {
+            "data"
+        }
+    }
+
+    impl<'a> Attr<&'a Data> for Email {
+        type Output = &'a str;
+
+        fn get(&self, i: &'a Data) -> &'a str {
+            i.email.as_ref()
+        }
+
+        fn name(&self) -> &'static str {
+            "email"
+        }
+    }
+}
+
+fn user() -> User {
+    User { name: "Florian".into(), age: 34, nickname: None, data: Data { email: "flo@andersground.net".into() } }
+}
+
+#[test]
+fn zip_paths() {
+    let user = user();
+
+    let path = zip((retrieve(user::Name), retrieve(user::Email).from(user::DataField), retrieve(user::Age)));
+
+    assert_eq!(path.traverse(&user), Ok(("Florian", "flo@andersground.net", 34)));
+}
+
+#[test]
+fn zip_fails_with_first_failure() {
+    let user = user();
+
+    let path = zip((retrieve(user::Name), retrieve_insecure(user::Nickname)));
+
+    assert!(path.traverse(&user).unwrap_err().is_missing());
+}
+
+#[test]
+fn project_into_struct() {
+    #[derive(Debug, PartialEq)]
+    struct UserDto<'a> {
+        name: &'a str,
+        email: &'a str,
+        age: u32,
+    }
+
+    let user = user();
+
+    let dto = project!(&user => UserDto {
+        name: retrieve(user::Name),
+        email: retrieve(user::Email).from(user::DataField),
+        age: retrieve(user::Age),
+    });
+
+    assert_eq!(dto, Ok(UserDto { name: "Florian", email: "flo@andersground.net", age: 34 }));
+}
+
+#[test]
+fn project_into_generic_struct() {
+    #[derive(Debug, PartialEq)]
+    struct Named<T> {
+        name: T,
+    }
+
+    let user = user();
+
+    let named = project!(&user => Named<&str> { name: retrieve(user::Name) });
+    assert_eq!(named, Ok(Named { name: "Florian" }));
+}
+
+#[test]
+fn project_with_early_returns() {
+    #[derive(Debug, PartialEq)]
+    struct Contact<'a> {
+        name: &'a str,
+        email: &'a str,
+    }
+
+    fn contact(user: &User, with_email: bool) -> attr::Result<Contact<'_>> {
+        project!(user => Contact {
+            name: Some(retrieve(user::Name)).ok_or_else(|| attr::Error::invalid("no name"))?,
+            email: if with_email { retrieve(user::Email).from(user::DataField) } else { return Err(attr::Error::missing("no email")) },
+        })
+    }
+
+    let user = user();
+    assert_eq!(contact(&user, true), Ok(Contact { name: "Florian", email: "flo@andersground.net" }));
+    assert!(contact(&user, false).unwrap_err().is_missing());
+}